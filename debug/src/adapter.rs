@@ -0,0 +1,142 @@
+// Items declared at the start of the generated `fmt` to format the fields
+// that need more than their own `Debug`. They are generic on their own, so
+// that they can be declared inside `fmt` and stay out of the user's namespace.
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+
+// Adapts the functions given with #[debug(with = ...)] to `Debug`.
+pub(crate) fn debug_with() -> TokenStream2 {
+    quote! {
+        struct __DebugWith<'a, T: ?::core::marker::Sized> {
+            value: &'a T,
+            fmt: fn(&T, &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result,
+        }
+
+        impl<T: ?::core::marker::Sized> ::core::fmt::Debug for __DebugWith<'_, T> {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                (self.fmt)(self.value, f)
+            }
+        }
+    }
+}
+
+// Formats a field under `max_items` and `max_len`. Which of the two applies
+// depends on the field's type, which the macro cannot see, so the field is
+// wrapped in a `__DebugLimit` and `(&&&limit).__limited()` is called. Method
+// resolution tries the impl on `&&__DebugLimit` first, then the one on
+// `&__DebugLimit` and then the one on `__DebugLimit` itself, and skips those
+// whose bounds the type does not meet: a collection is cut after `max_items`
+// items, a string after `max_len` characters, and anything else is printed
+// in full. Either way the value keeps its own `Debug` output unless something
+// is left out.
+pub(crate) fn debug_limit() -> TokenStream2 {
+    quote! {
+        struct __DebugLimit<'a, T: ?::core::marker::Sized> {
+            value: &'a T,
+            max_items: ::core::option::Option<usize>,
+            max_len: ::core::option::Option<usize>,
+        }
+
+        struct __DebugItems<'a, T: ?::core::marker::Sized> {
+            value: &'a T,
+            max: ::core::option::Option<usize>,
+        }
+
+        struct __DebugStr<'a, T: ?::core::marker::Sized> {
+            value: &'a T,
+            max: ::core::option::Option<usize>,
+        }
+
+        trait __DebugItemsKind<'a, T: ?::core::marker::Sized> {
+            fn __limited(&self) -> __DebugItems<'a, T>;
+        }
+
+        trait __DebugStrKind<'a, T: ?::core::marker::Sized> {
+            fn __limited(&self) -> __DebugStr<'a, T>;
+        }
+
+        trait __DebugAnyKind<'a, T: ?::core::marker::Sized> {
+            fn __limited(&self) -> &'a T;
+        }
+
+        impl<'a, T> __DebugItemsKind<'a, T> for &&__DebugLimit<'a, T>
+        where
+            T: ?::core::marker::Sized + ::core::fmt::Debug,
+            &'a T: ::core::iter::IntoIterator,
+            <&'a T as ::core::iter::IntoIterator>::Item: ::core::fmt::Debug,
+        {
+            fn __limited(&self) -> __DebugItems<'a, T> {
+                __DebugItems {
+                    value: self.value,
+                    max: self.max_items,
+                }
+            }
+        }
+
+        impl<'a, T> __DebugStrKind<'a, T> for &__DebugLimit<'a, T>
+        where
+            T: ?::core::marker::Sized + ::core::convert::AsRef<str>,
+        {
+            fn __limited(&self) -> __DebugStr<'a, T> {
+                __DebugStr {
+                    value: self.value,
+                    max: self.max_len,
+                }
+            }
+        }
+
+        impl<'a, T: ?::core::marker::Sized> __DebugAnyKind<'a, T> for __DebugLimit<'a, T> {
+            fn __limited(&self) -> &'a T {
+                self.value
+            }
+        }
+
+        impl<'a, T> ::core::fmt::Debug for __DebugItems<'a, T>
+        where
+            T: ?::core::marker::Sized + ::core::fmt::Debug,
+            &'a T: ::core::iter::IntoIterator,
+            <&'a T as ::core::iter::IntoIterator>::Item: ::core::fmt::Debug,
+        {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                let max = match self.max {
+                    ::core::option::Option::Some(max)
+                        if ::core::iter::IntoIterator::into_iter(self.value)
+                            .nth(max)
+                            .is_some() =>
+                    {
+                        max
+                    }
+                    _ => return ::core::fmt::Debug::fmt(self.value, f),
+                };
+                let mut items = ::core::iter::IntoIterator::into_iter(self.value);
+                let mut list = f.debug_list();
+                list.entries(::core::iter::Iterator::take(&mut items, max));
+                let more = ::core::iter::Iterator::count(items);
+                list.entry(&::core::format_args!("... ({} more)", more));
+                list.finish()
+            }
+        }
+
+        impl<T> ::core::fmt::Debug for __DebugStr<'_, T>
+        where
+            T: ?::core::marker::Sized + ::core::convert::AsRef<str>,
+        {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                let value = self.value.as_ref();
+                let cut = match self.max {
+                    ::core::option::Option::Some(max) => value.char_indices().nth(max),
+                    ::core::option::Option::None => ::core::option::Option::None,
+                };
+                match cut {
+                    ::core::option::Option::Some((cut, _)) => {
+                        ::core::fmt::Debug::fmt(&value[..cut], f)?;
+                        let more = value[cut..].chars().count();
+                        ::core::write!(f, "... ({} more)", more)
+                    }
+                    ::core::option::Option::None => ::core::fmt::Debug::fmt(value, f),
+                }
+            }
+        }
+    }
+}
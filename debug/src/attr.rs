@@ -0,0 +1,274 @@
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use syn::{
+    meta::ParseNestedMeta,
+    parse::{ParseStream, Parser},
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Attribute, Error, Expr, ExprLit, ExprPath, Lit, LitInt, LitStr, Meta, Result, Token,
+    WherePredicate,
+};
+
+// The derive whose attributes are read: #[debug] for CustomDebug and
+// #[display] for CustomDisplay.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Derive {
+    Debug,
+    Display,
+}
+
+impl Derive {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Derive::Debug => "CustomDebug",
+            Derive::Display => "CustomDisplay",
+        }
+    }
+
+    fn attr(self) -> &'static str {
+        match self {
+            Derive::Debug => "debug",
+            Derive::Display => "display",
+        }
+    }
+}
+
+// The places an attribute can be written.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Place {
+    Type,
+    Variant,
+    Field,
+}
+
+impl Place {
+    fn plural(self) -> &'static str {
+        match self {
+            Place::Type => "types",
+            Place::Variant => "variants",
+            Place::Field => "fields",
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Options {
+    // The format string of `#[debug = "..."]`.
+    pub format: Option<LitStr>,
+    // Formats the field through `fn(&T, &mut Formatter) -> fmt::Result`.
+    pub with: Option<ExprPath>,
+    // Cut collections after this many items and strings after this many
+    // characters. Given on the type, they apply to every field that is
+    // printed through its own `Debug`.
+    pub max_items: Option<usize>,
+    pub max_len: Option<usize>,
+    // Printed instead of the name of the type, variant or field.
+    pub rename: Option<LitStr>,
+    // Prints a struct with a single field as that field.
+    pub transparent: Option<Span>,
+    // Prints the fields of a field's own struct in place of the field.
+    pub flatten: Option<Span>,
+    // Leaves the field out, or prints a placeholder in place of its value.
+    pub skip: Option<Span>,
+    pub redact: Option<Span>,
+    // Implements `debug_visit::VisitFields` along with `Debug`.
+    pub visit: Option<Span>,
+    // The template of `#[display("...")]`.
+    pub template: Option<LitStr>,
+    // Replaces the inferred where clause.
+    pub bound: Option<Punctuated<WherePredicate, Token![,]>>,
+    // Every option given, along with the places it applies to, so that one
+    // written in the wrong place can be reported there.
+    given: Vec<(Span, &'static str, &'static [Place])>,
+}
+
+impl Options {
+    pub(crate) fn from_attrs(attrs: &[Attribute], derive: Derive, place: Place) -> Result<Self> {
+        let mut options = Options::default();
+        let mut errors: Option<Error> = None;
+        for attr in attrs {
+            if !attr.path().is_ident(derive.attr()) {
+                continue;
+            }
+            let parsed = match (&attr.meta, derive) {
+                (Meta::NameValue(_), Derive::Debug) => options.parse_format(attr),
+                (Meta::List(_), Derive::Debug) => {
+                    attr.parse_nested_meta(|meta| options.parse_meta(meta, derive))
+                }
+                (Meta::List(_), Derive::Display) => {
+                    attr.parse_args_with(|input: ParseStream| options.parse_display(input))
+                }
+                (_, Derive::Debug) => Err(Error::new_spanned(
+                    attr,
+                    "expected #[debug = \"...\"] or #[debug(...)]",
+                )),
+                (_, Derive::Display) => {
+                    Err(Error::new_spanned(attr, "expected #[display(\"...\")]"))
+                }
+            };
+            if let Err(error) = parsed {
+                match &mut errors {
+                    Some(errors) => errors.combine(error),
+                    None => errors = Some(error),
+                }
+            }
+        }
+        for (span, option, places) in &options.given {
+            if !places.contains(&place) {
+                let error = Error::new(
+                    *span,
+                    format!("`{}` does not apply to {}", option, place.plural()),
+                );
+                match &mut errors {
+                    Some(errors) => errors.combine(error),
+                    None => errors = Some(error),
+                }
+            }
+        }
+        if let (Some(_), Some(with)) = (&options.format, &options.with) {
+            let error = Error::new_spanned(with, "`with` cannot be combined with a format string");
+            match &mut errors {
+                Some(errors) => errors.combine(error),
+                None => errors = Some(error),
+            }
+        }
+        if options.format.is_some() || options.with.is_some() {
+            for (span, option, _) in &options.given {
+                if let "max_items" | "max_len" = *option {
+                    let error = Error::new(
+                        *span,
+                        format!(
+                            "`{}` cannot be combined with a format string or `with`",
+                            option
+                        ),
+                    );
+                    match &mut errors {
+                        Some(errors) => errors.combine(error),
+                        None => errors = Some(error),
+                    }
+                }
+            }
+        }
+        // A skipped field is not printed at all and a flattened one has no
+        // name or value of its own to adjust. A redacted field has no value to
+        // adjust, and a transparent struct no name.
+        for (span, option, _) in &options.given {
+            let conflict = match *option {
+                "skip" | "transparent" | "visit" | "bound" => None,
+                _ if options.skip.is_some() => Some("skip"),
+                "flatten" => None,
+                _ if options.flatten.is_some() => Some("flatten"),
+                "rename" if options.transparent.is_some() => Some("transparent"),
+                "rename" | "redact" => None,
+                _ if options.redact.is_some() => Some("redact"),
+                _ => None,
+            };
+            if let Some(conflict) = conflict {
+                let error = Error::new(
+                    *span,
+                    format!("`{}` cannot be combined with `{}`", option, conflict),
+                );
+                match &mut errors {
+                    Some(errors) => errors.combine(error),
+                    None => errors = Some(error),
+                }
+            }
+        }
+        match errors {
+            Some(errors) => Err(errors),
+            None => Ok(options),
+        }
+    }
+
+    fn parse_format(&mut self, attr: &Attribute) -> Result<()> {
+        let value = &attr.meta.require_name_value()?.value;
+        let Expr::Lit(ExprLit {
+            lit: Lit::Str(format),
+            ..
+        }) = value
+        else {
+            return Err(Error::new_spanned(value, "expected a format string"));
+        };
+        let format = format.clone();
+        self.given
+            .push((format.span(), "#[debug = \"...\"]", &[Place::Field]));
+        self.format = Some(format);
+        Ok(())
+    }
+
+    // `#[display("...", options...)]`, where both parts are optional.
+    fn parse_display(&mut self, input: ParseStream) -> Result<()> {
+        if input.peek(LitStr) {
+            let template: LitStr = input.parse()?;
+            self.given.push((
+                template.span(),
+                "#[display(\"...\")]",
+                &[Place::Type, Place::Variant],
+            ));
+            self.template = Some(template);
+            if input.is_empty() {
+                return Ok(());
+            }
+            input.parse::<Token![,]>()?;
+        }
+        let rest: TokenStream2 = input.parse()?;
+        syn::meta::parser(|meta| self.parse_meta(meta, Derive::Display)).parse2(rest)
+    }
+
+    fn parse_meta(&mut self, meta: ParseNestedMeta, derive: Derive) -> Result<()> {
+        let debug = derive == Derive::Debug;
+        if debug && meta.path.is_ident("with") {
+            let with: LitStr = meta.value()?.parse()?;
+            self.with = Some(with.parse()?);
+            self.given.push((meta.path.span(), "with", &[Place::Field]));
+        } else if debug && meta.path.is_ident("max_items") {
+            let max: LitInt = meta.value()?.parse()?;
+            self.max_items = Some(max.base10_parse()?);
+            self.given
+                .push((meta.path.span(), "max_items", &[Place::Type, Place::Field]));
+        } else if debug && meta.path.is_ident("max_len") {
+            let max: LitInt = meta.value()?.parse()?;
+            self.max_len = Some(max.base10_parse()?);
+            self.given
+                .push((meta.path.span(), "max_len", &[Place::Type, Place::Field]));
+        } else if debug && meta.path.is_ident("rename") {
+            self.rename = Some(meta.value()?.parse()?);
+            self.given.push((
+                meta.path.span(),
+                "rename",
+                &[Place::Type, Place::Variant, Place::Field],
+            ));
+        } else if debug && meta.path.is_ident("transparent") {
+            self.transparent = Some(meta.path.span());
+            self.given
+                .push((meta.path.span(), "transparent", &[Place::Type]));
+        } else if debug && meta.path.is_ident("flatten") {
+            self.flatten = Some(meta.path.span());
+            self.given
+                .push((meta.path.span(), "flatten", &[Place::Field]));
+        } else if debug && meta.path.is_ident("skip") {
+            self.skip = Some(meta.path.span());
+            self.given.push((meta.path.span(), "skip", &[Place::Field]));
+        } else if debug && meta.path.is_ident("redact") {
+            self.redact = Some(meta.path.span());
+            self.given
+                .push((meta.path.span(), "redact", &[Place::Field]));
+        } else if debug && meta.path.is_ident("visit") {
+            self.visit = Some(meta.path.span());
+            self.given.push((meta.path.span(), "visit", &[Place::Type]));
+        } else if meta.path.is_ident("bound") {
+            let bound: LitStr = meta.value()?.parse()?;
+            self.bound = Some(bound.parse_with(Punctuated::parse_terminated)?);
+            self.given.push((meta.path.span(), "bound", &[Place::Type]));
+        } else if debug {
+            return Err(meta.error(
+                "unsupported #[debug] option; expected `with = \"...\"`, `max_items = N`, \
+                 `max_len = N`, `rename = \"...\"`, `transparent`, `flatten`, `skip`, \
+                 `redact`, `visit` or `bound = \"...\"`",
+            ));
+        } else {
+            return Err(meta
+                .error("unsupported #[display] option; expected a template or `bound = \"...\"`"));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+use proc_macro2::TokenStream as TokenStream2;
+use syn::{
+    parse_quote,
+    visit::{self, Visit},
+    Generics, Ident, Type, TypePath, WherePredicate,
+};
+
+// Where-clause predicates requiring `bound` of everything in the field types
+// that depends on a type parameter: the parameter itself, or an associated
+// type of it such as `T::Value`. A parameter that only appears in
+// `PhantomData<T>` needs no bound. Whole field types are never bounded, so that
+// a field referring back to the type being derived, such as
+// `Option<Box<Two<T>>>`, does not make the bound recursive.
+pub(crate) fn infer<'a>(
+    generics: &Generics,
+    types: impl IntoIterator<Item = &'a Type>,
+    bound: &TokenStream2,
+) -> Vec<WherePredicate> {
+    let mut dependents = Dependents {
+        params: generics.type_params().map(|param| &param.ident).collect(),
+        found: vec![],
+    };
+    for ty in types {
+        dependents.visit_type(ty);
+    }
+    dependents
+        .found
+        .into_iter()
+        .map(|ty| parse_quote!(#ty: #bound))
+        .collect()
+}
+
+struct Dependents<'a> {
+    params: Vec<&'a Ident>,
+    found: Vec<Type>,
+}
+
+impl Dependents<'_> {
+    fn is_param(&self, ty: &Type) -> bool {
+        match ty {
+            Type::Path(TypePath { qself: None, path }) => path
+                .get_ident()
+                .is_some_and(|ident| self.params.contains(&ident)),
+            _ => false,
+        }
+    }
+
+    fn push(&mut self, ty: &TypePath) {
+        let ty = Type::Path(ty.clone());
+        if !self.found.contains(&ty) {
+            self.found.push(ty);
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Dependents<'_> {
+    fn visit_type_path(&mut self, ty: &'ast TypePath) {
+        let depends = match &ty.qself {
+            // `<T as Trait>::Value`
+            Some(qself) => self.is_param(&qself.ty),
+            // `T` or `T::Value`
+            None => {
+                ty.path.leading_colon.is_none()
+                    && self
+                        .params
+                        .contains(&&ty.path.segments.first().unwrap().ident)
+            }
+        };
+        if depends {
+            self.push(ty);
+        } else if ty.qself.is_some() || ty.path.segments.last().unwrap().ident != "PhantomData" {
+            visit::visit_type_path(self, ty);
+        }
+    }
+}
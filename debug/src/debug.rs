@@ -0,0 +1,290 @@
+use crate::attr::Options;
+use crate::{adapter, bound, visit, Body, Errors, Field, Input, Shape, Style};
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{quote, quote_spanned};
+use syn::{ext::IdentExt, spanned::Spanned, Error, Ident, Result};
+
+pub(crate) fn expand(input: &Input) -> Result<TokenStream2> {
+    check(input)?;
+    let ident = &input.ident;
+    let formatter = Ident::new("f", Span::mixed_site());
+    let debug = Ident::new("debug", Span::mixed_site());
+    let type_name = name(&input.options, ident);
+    // A struct with named fields lists them in `__debug_fields`, which the
+    // structs it is flattened into call as well.
+    let (body, debug_fields) = match &input.body {
+        Body::Struct(shape) if input.options.transparent.is_some() => {
+            let field = &shape.fields[0];
+            let member = &field.member;
+            let binding = &field.binding;
+            let value = value(field);
+            let body = quote! {
+                match self {
+                    Self { #member: #binding } => ::core::fmt::Debug::fmt(#value, #formatter),
+                }
+            };
+            (body, None)
+        }
+        Body::Struct(
+            shape @ Shape {
+                style: Style::Named,
+                ..
+            },
+        ) => {
+            let body = quote! {
+                let #debug = &mut #formatter.debug_struct(#type_name);
+                self.__debug_fields(#debug);
+                #debug.finish()
+            };
+            let pattern = pattern(shape);
+            let statements = shape.fields.iter().map(|field| statement(field, &debug));
+            let debug_fields = quote! {
+                match self {
+                    Self #pattern => { #(#statements)* }
+                }
+            };
+            (body, Some(debug_fields))
+        }
+        Body::Struct(shape) => {
+            let arm = arm(shape, quote!(Self), &type_name, &formatter, &debug);
+            (quote!(match self { #arm }), None)
+        }
+        // Matching on `*self` lets an enum without variants have no arms.
+        Body::Enum(variants) if variants.is_empty() => (quote!(match *self {}), None),
+        Body::Enum(variants) => {
+            let arms = variants.iter().map(|variant| {
+                let variant_ident = &variant.ident;
+                arm(
+                    &variant.shape,
+                    quote!(Self::#variant_ident),
+                    &name(&variant.options, variant_ident),
+                    &formatter,
+                    &debug,
+                )
+            });
+            (quote!(match self { #(#arms)* }), None)
+        }
+    };
+    // The adapters go where the fields are formatted.
+    let adapters = adapters(input);
+    let (body, debug_fields) = match debug_fields {
+        Some(debug_fields) => (
+            body,
+            Some(quote! {
+                #adapters
+                #debug_fields
+            }),
+        ),
+        None => (
+            quote! {
+                #adapters
+                #body
+            },
+            None,
+        ),
+    };
+    // Fields formatted by a function of their own, and those whose value is
+    // never printed, need no bound.
+    let predicates = match &input.options.bound {
+        Some(bound) => bound.iter().cloned().collect(),
+        None => bound::infer(
+            &input.generics,
+            input
+                .fields()
+                .filter(|field| {
+                    field.options.with.is_none()
+                        && field.options.skip.is_none()
+                        && field.options.redact.is_none()
+                })
+                .map(|field| &field.ty),
+            &quote!(::core::fmt::Debug),
+        ),
+    };
+    let mut generics = input.generics.clone();
+    generics.make_where_clause().predicates.extend(predicates);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let debug_fields = debug_fields.map(|debug_fields| {
+        quote! {
+            impl #impl_generics #ident #ty_generics #where_clause {
+                #[doc(hidden)]
+                pub fn __debug_fields(&self, #debug: &mut ::core::fmt::DebugStruct<'_, '_>) {
+                    #debug_fields
+                }
+            }
+        }
+    });
+    let visit_fields = input.options.visit.map(|_| visit::expand(input, &generics));
+    Ok(quote! {
+        impl #impl_generics ::core::fmt::Debug for #ident #ty_generics #where_clause {
+            fn fmt(&self, #formatter: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                #body
+            }
+        }
+
+        #debug_fields
+
+        #visit_fields
+    })
+}
+
+// Options that depend on the shape of the type.
+fn check(input: &Input) -> Result<()> {
+    let mut errors = Errors::default();
+    if let Some(span) = input.options.transparent {
+        match &input.body {
+            Body::Struct(shape) if shape.fields.len() == 1 => {
+                if let Some(span) = shape.fields[0].options.skip {
+                    errors.push(Error::new(
+                        span,
+                        "the field of a `transparent` struct cannot be skipped",
+                    ));
+                }
+            }
+            _ => errors.push(Error::new(
+                span,
+                "`transparent` expects a struct with exactly one field",
+            )),
+        }
+    }
+    let shapes: Vec<&Shape> = match &input.body {
+        Body::Struct(shape) => vec![shape],
+        Body::Enum(variants) => variants.iter().map(|variant| &variant.shape).collect(),
+    };
+    for shape in shapes {
+        if let Style::Named = shape.style {
+            continue;
+        }
+        for field in &shape.fields {
+            if let Some(span) = field.options.flatten {
+                errors.push(Error::new(span, "`flatten` expects a named field"));
+            }
+        }
+    }
+    errors.finish()
+}
+
+// The adapters that the fields of `input` are formatted through.
+pub(crate) fn adapters(input: &Input) -> TokenStream2 {
+    let debug_with = input
+        .fields()
+        .any(|field| field.options.with.is_some())
+        .then(adapter::debug_with);
+    let debug_limit = input.fields().any(is_limited).then(adapter::debug_limit);
+    quote! {
+        #debug_with
+        #debug_limit
+    }
+}
+
+pub(crate) fn name(options: &Options, ident: &Ident) -> String {
+    match &options.rename {
+        Some(rename) => rename.value(),
+        None => ident.unraw().to_string(),
+    }
+}
+
+// `{ member: binding, ... }`, binding every field of `shape`.
+pub(crate) fn pattern(shape: &Shape) -> TokenStream2 {
+    let members = shape.fields.iter().map(|field| &field.member);
+    let bindings = shape.fields.iter().map(|field| &field.binding);
+    quote!({ #(#members: #bindings),* })
+}
+
+// A match arm formatting the fields bound by the pattern `path { ... }`.
+fn arm(
+    shape: &Shape,
+    path: TokenStream2,
+    name: &str,
+    formatter: &Ident,
+    debug: &Ident,
+) -> TokenStream2 {
+    let pattern = pattern(shape);
+    let body = match shape.style {
+        Style::Named => {
+            let statements = shape.fields.iter().map(|field| statement(field, debug));
+            quote!({
+                let #debug = &mut #formatter.debug_struct(#name);
+                #(#statements)*
+                #debug.finish()
+            })
+        }
+        Style::Tuple => {
+            let values = shape
+                .fields
+                .iter()
+                .filter(|field| field.options.skip.is_none())
+                .map(value);
+            quote! {
+                #formatter.debug_tuple(#name)
+                    #(.field(#values))*
+                    .finish()
+            }
+        }
+        Style::Unit => quote!(#formatter.write_str(#name)),
+    };
+    quote! {
+        #path #pattern => #body,
+    }
+}
+
+// Adds a named field to the `DebugStruct` at `debug`, or the fields of its own
+// struct if it is flattened.
+fn statement(field: &Field, debug: &Ident) -> TokenStream2 {
+    let binding = &field.binding;
+    if field.options.skip.is_some() {
+        return TokenStream2::new();
+    }
+    if field.options.flatten.is_some() {
+        return quote_spanned! {field.ty.span()=>
+            #binding.__debug_fields(#debug);
+        };
+    }
+    let name = field_name(field);
+    let value = value(field);
+    quote!(#debug.field(#name, #value);)
+}
+
+pub(crate) fn field_name(field: &Field) -> String {
+    match &field.options.rename {
+        Some(rename) => rename.value(),
+        None => field.name(),
+    }
+}
+
+// The field as it is printed, as an expression of type `&impl Debug`.
+pub(crate) fn value(field: &Field) -> TokenStream2 {
+    let binding = &field.binding;
+    if field.options.redact.is_some() {
+        return quote!(&::core::format_args!("[redacted]"));
+    }
+    if let Some(with) = &field.options.with {
+        return quote!(&__DebugWith { value: #binding, fmt: #with });
+    }
+    if let Some(format) = &field.options.format {
+        return quote!(&::core::format_args!(#format, #binding));
+    }
+    if is_limited(field) {
+        let max_items = quote_option(field.options.max_items);
+        let max_len = quote_option(field.options.max_len);
+        return quote! {
+            &(&&&__DebugLimit {
+                value: #binding,
+                max_items: #max_items,
+                max_len: #max_len,
+            })
+                .__limited()
+        };
+    }
+    quote!(#binding)
+}
+
+fn is_limited(field: &Field) -> bool {
+    field.options.max_items.is_some() || field.options.max_len.is_some()
+}
+
+fn quote_option(max: Option<usize>) -> TokenStream2 {
+    match max {
+        Some(max) => quote!(::core::option::Option::Some(#max)),
+        None => quote!(::core::option::Option::None),
+    }
+}
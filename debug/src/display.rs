@@ -0,0 +1,185 @@
+// #[derive(CustomDisplay)] writes the template given with #[display("...")]
+// on a struct, or on each variant of an enum. The fields named in a template
+// are bound under their own names for `write!` to capture, so that a name
+// that is not a field is reported by the compiler inside the template.
+
+use crate::{bound, Body, Errors, Field, Input, Shape};
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{Error, Ident, LitStr, Member, Result, Type};
+
+pub(crate) fn expand(input: &Input) -> Result<TokenStream2> {
+    let ident = &input.ident;
+    let formatter = Ident::new("f", Span::mixed_site());
+    let mut errors = Errors::default();
+    // The fields each template formats, with the trait it formats them by.
+    let mut uses: Vec<(&Type, &'static str)> = vec![];
+    let body = match &input.body {
+        Body::Struct(shape) => match &input.options.template {
+            Some(template) => {
+                let arm = arm(shape, quote!(Self), template, &formatter, &mut uses);
+                quote!(match self { #arm })
+            }
+            None => return Err(Error::new_spanned(ident, MISSING_TEMPLATE)),
+        },
+        Body::Enum(variants) => {
+            if let Some(template) = &input.options.template {
+                errors.push(Error::new_spanned(
+                    template,
+                    "an enum takes a #[display(\"...\")] template on each variant",
+                ));
+            }
+            let arms: Vec<TokenStream2> = variants
+                .iter()
+                .filter_map(|variant| {
+                    let variant_ident = &variant.ident;
+                    match &variant.options.template {
+                        Some(template) => Some(arm(
+                            &variant.shape,
+                            quote!(Self::#variant_ident),
+                            template,
+                            &formatter,
+                            &mut uses,
+                        )),
+                        None => {
+                            errors.push(Error::new_spanned(variant_ident, MISSING_TEMPLATE));
+                            None
+                        }
+                    }
+                })
+                .collect();
+            // Matching on `*self` lets an enum without variants have no arms.
+            if variants.is_empty() {
+                quote!(match *self {})
+            } else {
+                quote!(match self { #(#arms)* })
+            }
+        }
+    };
+    errors.finish()?;
+    let predicates = match &input.options.bound {
+        Some(bound) => bound.iter().cloned().collect(),
+        None => {
+            let mut predicates = vec![];
+            for (ty, trait_name) in &uses {
+                let trait_ident = Ident::new(trait_name, Span::call_site());
+                let bound = quote!(::core::fmt::#trait_ident);
+                for predicate in bound::infer(&input.generics, [*ty], &bound) {
+                    if !predicates.contains(&predicate) {
+                        predicates.push(predicate);
+                    }
+                }
+            }
+            predicates
+        }
+    };
+    let mut generics = input.generics.clone();
+    generics.make_where_clause().predicates.extend(predicates);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::core::fmt::Display for #ident #ty_generics #where_clause {
+            fn fmt(&self, #formatter: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                #body
+            }
+        }
+    })
+}
+
+const MISSING_TEMPLATE: &str = "missing #[display(\"...\")] template";
+
+// A match arm binding the fields that `template` names and writing it. Tuple
+// fields are named `_0`, `_1` and so on.
+fn arm<'a>(
+    shape: &'a Shape,
+    path: TokenStream2,
+    template: &LitStr,
+    formatter: &Ident,
+    uses: &mut Vec<(&'a Type, &'static str)>,
+) -> TokenStream2 {
+    let mut members: Vec<&Member> = vec![];
+    let mut patterns: Vec<TokenStream2> = vec![];
+    for (name, trait_name) in template_args(&template.value()) {
+        let Some(field) = shape
+            .fields
+            .iter()
+            .find(|field| template_name(field) == name)
+        else {
+            continue;
+        };
+        if let Some(trait_name) = trait_name {
+            uses.push((&field.ty, trait_name));
+        }
+        if !members.contains(&&field.member) {
+            let binding = Ident::new(&name, template.span());
+            patterns.push(match &field.member {
+                Member::Named(_) => quote!(#binding),
+                Member::Unnamed(index) => quote!(#index: #binding),
+            });
+            members.push(&field.member);
+        }
+    }
+    quote! {
+        #path { #(#patterns,)* .. } => ::core::write!(#formatter, #template),
+    }
+}
+
+fn template_name(field: &Field) -> String {
+    match &field.member {
+        Member::Named(ident) => ident.to_string(),
+        Member::Unnamed(index) => format!("_{}", index.index),
+    }
+}
+
+// The names a template refers to, each with the formatting trait it is
+// written with. Names used as a width or precision, as in `{name:>width$}`,
+// are only read as a `usize` and come without a trait.
+fn template_args(template: &str) -> Vec<(String, Option<&'static str>)> {
+    let mut args = vec![];
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        rest = &rest[open + 1..];
+        if let Some(after) = rest.strip_prefix('{') {
+            rest = after;
+            continue;
+        }
+        let Some(close) = rest.find('}') else {
+            break;
+        };
+        let (arg, spec) = rest[..close]
+            .split_once(':')
+            .unwrap_or((&rest[..close], ""));
+        let arg = arg.trim();
+        if is_name(arg) {
+            args.push((arg.to_owned(), Some(trait_name(spec))));
+        }
+        for (dollar, _) in spec.match_indices('$') {
+            let start = spec[..dollar]
+                .rfind(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .map_or(0, |i| i + 1);
+            if is_name(&spec[start..dollar]) {
+                args.push((spec[start..dollar].to_owned(), None));
+            }
+        }
+        rest = &rest[close + 1..];
+    }
+    args
+}
+
+fn is_name(arg: &str) -> bool {
+    arg.starts_with(|c: char| c.is_alphabetic() || c == '_')
+}
+
+fn trait_name(spec: &str) -> &'static str {
+    if spec.ends_with('?') {
+        return "Debug";
+    }
+    match spec.chars().last() {
+        Some('x') => "LowerHex",
+        Some('X') => "UpperHex",
+        Some('o') => "Octal",
+        Some('b') => "Binary",
+        Some('e') => "LowerExp",
+        Some('E') => "UpperExp",
+        _ => "Display",
+    }
+}
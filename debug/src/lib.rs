@@ -1,8 +1,199 @@
+mod adapter;
+mod attr;
+mod bound;
+mod debug;
+mod display;
+mod visit;
+
+use crate::attr::{Derive, Options, Place};
 use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::format_ident;
+use syn::{
+    ext::IdentExt, parse_macro_input, Data, DeriveInput, Error, Fields, Generics, Ident, Member,
+    Result, Type,
+};
 
-#[proc_macro_derive(CustomDebug)]
+#[proc_macro_derive(CustomDebug, attributes(debug))]
 pub fn derive(input: TokenStream) -> TokenStream {
-    let _ = input;
+    let input = parse_macro_input!(input as DeriveInput);
+    match Input::new(input, Derive::Debug).and_then(|input| debug::expand(&input)) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.into_compile_error().into(),
+    }
+}
+
+#[proc_macro_derive(CustomDisplay, attributes(display))]
+pub fn derive_display(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match Input::new(input, Derive::Display).and_then(|input| display::expand(&input)) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.into_compile_error().into(),
+    }
+}
+
+// The type being derived, along with the options given to it, its variants
+// and its fields with the attributes of the derive at hand.
+struct Input {
+    ident: Ident,
+    generics: Generics,
+    options: Options,
+    body: Body,
+}
+
+enum Body {
+    Struct(Shape),
+    Enum(Vec<Variant>),
+}
+
+struct Variant {
+    ident: Ident,
+    options: Options,
+    shape: Shape,
+}
+
+// The fields of a struct or of one enum variant.
+struct Shape {
+    style: Style,
+    fields: Vec<Field>,
+}
+
+enum Style {
+    Named,
+    Tuple,
+    Unit,
+}
+
+struct Field {
+    member: Member,
+    // What the field is bound to while formatting. The span keeps it apart
+    // from anything named in the user's own tokens, such as a format string.
+    binding: Ident,
+    ty: Type,
+    options: Options,
+}
+
+impl Input {
+    fn new(input: DeriveInput, derive: Derive) -> Result<Self> {
+        let mut errors = Errors::default();
+        let options = errors.check(Options::from_attrs(&input.attrs, derive, Place::Type));
+        let body = match input.data {
+            Data::Struct(data) => {
+                Body::Struct(Shape::new(&data.fields, derive, &options, &mut errors))
+            }
+            Data::Enum(data) => Body::Enum(
+                data.variants
+                    .iter()
+                    .map(|variant| Variant {
+                        ident: variant.ident.clone(),
+                        options: errors.check(Options::from_attrs(
+                            &variant.attrs,
+                            derive,
+                            Place::Variant,
+                        )),
+                        shape: Shape::new(&variant.fields, derive, &options, &mut errors),
+                    })
+                    .collect(),
+            ),
+            Data::Union(data) => {
+                return Err(Error::new(
+                    data.union_token.span,
+                    format!("{} does not support unions", derive.name()),
+                ))
+            }
+        };
+        errors.finish()?;
+        Ok(Self {
+            ident: input.ident,
+            generics: input.generics,
+            options,
+            body,
+        })
+    }
+
+    fn fields(&self) -> Box<dyn Iterator<Item = &Field> + '_> {
+        match &self.body {
+            Body::Struct(shape) => Box::new(shape.fields.iter()),
+            Body::Enum(variants) => Box::new(
+                variants
+                    .iter()
+                    .flat_map(|variant| variant.shape.fields.iter()),
+            ),
+        }
+    }
+}
+
+impl Shape {
+    // `defaults` are the options given on the type.
+    fn new(fields: &Fields, derive: Derive, defaults: &Options, errors: &mut Errors) -> Self {
+        let style = match fields {
+            Fields::Named(_) => Style::Named,
+            Fields::Unnamed(_) => Style::Tuple,
+            Fields::Unit => Style::Unit,
+        };
+        let fields = fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| Field {
+                member: match &field.ident {
+                    Some(ident) => Member::Named(ident.clone()),
+                    None => Member::Unnamed(index.into()),
+                },
+                binding: format_ident!("__field{}", index, span = Span::mixed_site()),
+                ty: field.ty.clone(),
+                options: {
+                    let mut options =
+                        errors.check(Options::from_attrs(&field.attrs, derive, Place::Field));
+                    if options.format.is_none()
+                        && options.with.is_none()
+                        && options.flatten.is_none()
+                        && options.skip.is_none()
+                        && options.redact.is_none()
+                    {
+                        options.max_items = options.max_items.or(defaults.max_items);
+                        options.max_len = options.max_len.or(defaults.max_len);
+                    }
+                    options
+                },
+            })
+            .collect();
+        Self { style, fields }
+    }
+}
+
+impl Field {
+    fn name(&self) -> String {
+        match &self.member {
+            Member::Named(ident) => ident.unraw().to_string(),
+            Member::Unnamed(index) => index.index.to_string(),
+        }
+    }
+}
+
+// Errors from every part of the input, so that all of them are reported at
+// once.
+#[derive(Default)]
+struct Errors(Option<Error>);
+
+impl Errors {
+    fn push(&mut self, error: Error) {
+        match &mut self.0 {
+            Some(errors) => errors.combine(error),
+            None => self.0 = Some(error),
+        }
+    }
+
+    fn check<T: Default>(&mut self, result: Result<T>) -> T {
+        result.unwrap_or_else(|error| {
+            self.push(error);
+            T::default()
+        })
+    }
 
-    unimplemented!()
+    fn finish(self) -> Result<()> {
+        match self.0 {
+            Some(errors) => Err(errors),
+            None => Ok(()),
+        }
+    }
 }
@@ -0,0 +1,64 @@
+// #[debug(visit)] implements `debug_visit::VisitFields`, which hands each
+// field to a visitor under the name and with the value that `Debug` prints.
+
+use crate::debug::{adapters, field_name, pattern, value};
+use crate::{Body, Field, Input, Shape};
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{quote, quote_spanned};
+use syn::{spanned::Spanned, Generics, Ident};
+
+// `generics` carry the where clause of the `Debug` impl.
+pub(crate) fn expand(input: &Input, generics: &Generics) -> TokenStream2 {
+    let ident = &input.ident;
+    let visitor = Ident::new("visitor", Span::mixed_site());
+    let body = match &input.body {
+        Body::Struct(shape) => {
+            let arm = arm(shape, quote!(Self), &visitor);
+            quote!(match self { #arm })
+        }
+        // Matching on `*self` lets an enum without variants have no arms.
+        Body::Enum(variants) if variants.is_empty() => quote!(match *self {}),
+        Body::Enum(variants) => {
+            let arms = variants.iter().map(|variant| {
+                let variant_ident = &variant.ident;
+                arm(&variant.shape, quote!(Self::#variant_ident), &visitor)
+            });
+            quote!(match self { #(#arms)* })
+        }
+    };
+    let adapters = adapters(input);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        impl #impl_generics ::debug_visit::VisitFields for #ident #ty_generics #where_clause {
+            fn visit_fields(&self, #visitor: &mut dyn ::debug_visit::FieldVisitor) {
+                #adapters
+                #body
+            }
+        }
+    }
+}
+
+fn arm(shape: &Shape, path: TokenStream2, visitor: &Ident) -> TokenStream2 {
+    let pattern = pattern(shape);
+    let visits = shape.fields.iter().map(|field| visit(field, visitor));
+    quote! {
+        #path #pattern => { #(#visits)* }
+    }
+}
+
+// Skipped fields are not visited, and flattened ones are visited through the
+// `VisitFields` of their own struct.
+fn visit(field: &Field, visitor: &Ident) -> TokenStream2 {
+    let binding = &field.binding;
+    if field.options.skip.is_some() {
+        return TokenStream2::new();
+    }
+    if field.options.flatten.is_some() {
+        return quote_spanned! {field.ty.span()=>
+            ::debug_visit::VisitFields::visit_fields(#binding, #visitor);
+        };
+    }
+    let name = field_name(field);
+    let value = value(field);
+    quote!(#visitor.visit_field(#name, #value);)
+}
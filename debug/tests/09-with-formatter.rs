@@ -0,0 +1,76 @@
+// Some output cannot be written as a format string, such as a duration in
+// milliseconds or bytes in hex. #[debug(with = "path::to::function")] formats
+// the field through a `fn(&T, &mut fmt::Formatter) -> fmt::Result` instead.
+//
+// The field keeps its place in the struct's output and does not need to
+// implement Debug itself, so it adds no bound on the type parameters. The
+// function may be generic, in which case its own bounds can be added with
+// #[debug(bound = "...")].
+
+use derive_debug::CustomDebug;
+use std::fmt;
+use std::time::Duration;
+
+mod hex {
+    use std::fmt;
+
+    pub fn encode<T: AsRef<[u8]>>(bytes: &T, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in bytes.as_ref() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+fn millis(duration: &Duration, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}ms", duration.as_millis())
+}
+
+#[derive(CustomDebug)]
+pub struct Request {
+    id: u32,
+    #[debug(with = "millis")]
+    elapsed: Duration,
+    #[debug(with = "hex::encode")]
+    digest: [u8; 4],
+}
+
+// Does not implement Debug.
+pub struct Key(Vec<u8>);
+
+impl AsRef<[u8]> for Key {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+#[derive(CustomDebug)]
+#[debug(bound = "T: AsRef<[u8]>")]
+pub struct Signed<T> {
+    #[debug(with = "hex::encode")]
+    key: T,
+}
+
+#[derive(CustomDebug)]
+pub enum Event {
+    Timeout(#[debug(with = "millis")] Duration),
+}
+
+fn main() {
+    let request = Request {
+        id: 7,
+        elapsed: Duration::from_micros(1_500_000),
+        digest: [0xde, 0xad, 0xbe, 0xef],
+    };
+    let debug = format!("{:?}", request);
+    let expected = "Request { id: 7, elapsed: 1500ms, digest: deadbeef }";
+    assert_eq!(debug, expected);
+
+    let signed = Signed {
+        key: Key(vec![1, 2]),
+    };
+    assert_eq!(format!("{:?}", signed), "Signed { key: 0102 }");
+
+    let event = Event::Timeout(Duration::from_millis(30));
+    assert_eq!(format!("{:?}", event), "Timeout(30ms)");
+}
@@ -0,0 +1,32 @@
+// Options are reported where they are written when they do not apply there,
+// when they are unknown, or when they conflict. A function given with
+// #[debug(with = ...)] must take a reference to the field's type.
+
+use derive_debug::CustomDebug;
+use std::fmt;
+
+fn millis(millis: &u64, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}ms", millis)
+}
+
+#[derive(CustomDebug)]
+#[debug(with = "millis")]
+pub struct Timer {
+    #[debug(bound = "T: Debug")]
+    start: u64,
+    #[debug = "{}s"]
+    #[debug(with = "millis")]
+    elapsed: u64,
+    #[debug(hex)]
+    id: u32,
+    #[debug(with = "millis", max_len = 8)]
+    lap: u64,
+}
+
+#[derive(CustomDebug)]
+pub struct Span {
+    #[debug(with = "millis")]
+    seconds: u32,
+}
+
+fn main() {}
@@ -0,0 +1,53 @@
+// #[debug(max_items = N)] prints the first N items of a collection followed by
+// `... (M more)`, and #[debug(max_len = N)] does the same with the characters
+// of a string. Either works for any collection that can be iterated by
+// reference, and nothing is collected to print it.
+//
+// Given on the type, they apply to every field. Fields that are neither
+// collections nor strings are printed in full, and so are the ones within the
+// limit, which keep their own format.
+
+use derive_debug::CustomDebug;
+use std::collections::BTreeMap;
+
+#[derive(CustomDebug)]
+pub struct Packet {
+    id: u32,
+    #[debug(max_items = 4)]
+    payload: Vec<u8>,
+    #[debug(max_len = 5)]
+    source: String,
+}
+
+#[derive(CustomDebug)]
+#[debug(max_items = 2, max_len = 8)]
+pub struct Batch<T> {
+    name: &'static str,
+    items: Vec<T>,
+    tags: BTreeMap<&'static str, u8>,
+    retry: Option<u8>,
+    #[debug(max_items = 1)]
+    shards: [u16; 3],
+}
+
+fn main() {
+    let packet = Packet {
+        id: 1,
+        payload: vec![0; 10_000_000],
+        source: "localhost".to_owned(),
+    };
+    let debug = format!("{:?}", packet);
+    let expected = r#"Packet { id: 1, payload: [0, 0, 0, 0, ... (9999996 more)], source: "local"... (4 more) }"#;
+    assert_eq!(debug, expected);
+
+    let batch = Batch {
+        name: "nightly",
+        items: vec!['a', 'b', 'c'],
+        tags: [("env", 1), ("team", 2)].into_iter().collect(),
+        retry: Some(3),
+        shards: [1, 2, 3],
+    };
+    let debug = format!("{:?}", batch);
+    let expected = r#"Batch { name: "nightly", items: ['a', 'b', ... (1 more)], tags: {"env": 1, "team": 2}, retry: Some(3), shards: [1, ... (2 more)] }"#;
+    assert_eq!(debug, expected);
+}
@@ -0,0 +1,65 @@
+// #[derive(CustomDisplay)] implements Display from a template given with
+// #[display("...")], in which fields are referred to by name the way
+// `format!` refers to variables in scope. An enum takes a template on each
+// variant, and the fields of tuple structs and variants are named `_0`, `_1`
+// and so on.
+//
+// Bounds are inferred as they are for CustomDebug, using the trait each field
+// is formatted with, and can be replaced with #[display(bound = "...")].
+
+use derive_debug::{CustomDebug, CustomDisplay};
+use std::fmt::Display;
+
+#[derive(CustomDebug, CustomDisplay)]
+#[display("{host}:{port}")]
+pub struct Endpoint {
+    host: String,
+    port: u16,
+    retries: u8,
+}
+
+#[derive(CustomDisplay)]
+pub enum Shape<T> {
+    #[display("circle of radius {radius:.1}")]
+    Circle { radius: f64 },
+    #[display("{_0} by {_1:?}")]
+    Rect(T, T),
+    #[display("point")]
+    Point,
+    #[display("{mask:#06x} ({label:>width$})")]
+    Masked {
+        mask: u16,
+        label: &'static str,
+        width: usize,
+    },
+}
+
+#[derive(CustomDisplay)]
+#[display("<{value}>", bound = "T: Display + Copy")]
+pub struct Tagged<T> {
+    value: T,
+}
+
+fn main() {
+    let endpoint = Endpoint {
+        host: "localhost".to_owned(),
+        port: 8080,
+        retries: 3,
+    };
+    assert_eq!(endpoint.to_string(), "localhost:8080");
+    assert!(format!("{:?}", endpoint).contains("retries: 3"));
+
+    let circle = Shape::<u8>::Circle { radius: 1.0 };
+    assert_eq!(circle.to_string(), "circle of radius 1.0");
+    assert_eq!(Shape::Rect(2, 3).to_string(), "2 by 3");
+    assert_eq!(Shape::Rect("a", "b").to_string(), "a by \"b\"");
+    assert_eq!(Shape::<u8>::Point.to_string(), "point");
+    let masked = Shape::<u8>::Masked {
+        mask: 0xff,
+        label: "low",
+        width: 5,
+    };
+    assert_eq!(masked.to_string(), "0x00ff (  low)");
+
+    assert_eq!(Tagged { value: 'x' }.to_string(), "<x>");
+}
@@ -0,0 +1,13 @@
+// A name in a template that is not a field is reported by the compiler at that
+// name within the template.
+
+use derive_debug::CustomDisplay;
+
+#[derive(CustomDisplay)]
+#[display("{hots}:{port}")]
+pub struct Endpoint {
+    host: String,
+    port: u16,
+}
+
+fn main() {}
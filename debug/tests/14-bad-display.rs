@@ -0,0 +1,30 @@
+// Every struct and every variant needs a template, and an enum cannot have one
+// of its own. Options of CustomDebug do not apply to CustomDisplay.
+
+use derive_debug::CustomDisplay;
+
+#[derive(CustomDisplay)]
+pub struct Plain {
+    name: String,
+}
+
+#[derive(CustomDisplay)]
+#[display("any state")]
+pub enum State {
+    #[display("idle")]
+    Idle,
+    Busy(u8),
+}
+
+#[derive(CustomDisplay)]
+pub enum Mode {
+    Fixed(#[display("{_0}")] u8),
+}
+
+#[derive(CustomDisplay)]
+#[display("{count}", max_items = 2)]
+pub struct Counter {
+    count: u32,
+}
+
+fn main() {}
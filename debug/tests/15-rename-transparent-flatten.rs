@@ -0,0 +1,79 @@
+// #[debug(rename = "...")] prints another name for a type, a variant or a
+// field. #[debug(transparent)] prints a struct with a single field as that
+// field, and #[debug(flatten)] prints the fields of a field's own struct in
+// its place, as if they belonged to the outer struct.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct UserId(u64);
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Token {
+    #[debug(max_len = 4)]
+    secret: String,
+}
+
+#[derive(CustomDebug)]
+pub struct Audit {
+    created: u32,
+    #[debug(rename = "by")]
+    created_by: UserId,
+}
+
+#[derive(CustomDebug)]
+#[debug(rename = "User")]
+pub struct UserRecord {
+    id: UserId,
+    #[debug(rename = "type")]
+    r#type: &'static str,
+    #[debug(flatten)]
+    audit: Audit,
+    token: Token,
+}
+
+#[derive(CustomDebug)]
+pub enum Event {
+    #[debug(rename = "Login")]
+    SignIn {
+        #[debug(flatten)]
+        audit: Audit,
+        #[debug(rename = "ok")]
+        success: bool,
+    },
+    Logout(UserId),
+}
+
+fn main() {
+    let audit = Audit {
+        created: 7,
+        created_by: UserId(1),
+    };
+    let user = UserRecord {
+        id: UserId(42),
+        r#type: "admin",
+        audit,
+        token: Token {
+            secret: "hunter2".to_owned(),
+        },
+    };
+    let debug = format!("{:?}", user);
+    let expected =
+        r#"User { id: 42, type: "admin", created: 7, by: 1, token: "hunt"... (3 more) }"#;
+    assert_eq!(debug, expected);
+
+    let event = Event::SignIn {
+        audit: Audit {
+            created: 9,
+            created_by: UserId(2),
+        },
+        success: true,
+    };
+    let debug = format!("{:?}", event);
+    assert_eq!(debug, "Login { created: 9, by: 2, ok: true }");
+
+    let debug = format!("{:?}", Event::Logout(UserId(3)));
+    assert_eq!(debug, "Logout(3)");
+}
@@ -0,0 +1,37 @@
+// `transparent` goes on a struct with exactly one field, and `flatten` on a
+// named field. A flattened field is printed through the fields of its own
+// struct, so it takes no other option, and a transparent struct has no name
+// of its own to rename.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Pair {
+    #[debug(transparent)]
+    left: u8,
+    right: u8,
+}
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Both(u8, u8);
+
+#[derive(CustomDebug)]
+#[debug(transparent, rename = "Inner")]
+pub struct Wrapper(u8);
+
+#[derive(CustomDebug)]
+pub struct Inner {
+    value: u8,
+}
+
+#[derive(CustomDebug)]
+pub struct Outer {
+    #[debug(flatten, rename = "inner")]
+    inner: Inner,
+}
+
+#[derive(CustomDebug)]
+pub struct Positional(#[debug(flatten)] Inner);
+
+fn main() {}
@@ -0,0 +1,102 @@
+// #[debug(skip)] leaves a field out and #[debug(redact)] prints a placeholder
+// in place of its value.
+//
+// #[debug(visit)] also implements `debug_visit::VisitFields`, which hands
+// each field to a `FieldVisitor` as a name and a `&dyn Debug`, the way the
+// `Debug` impl prints it. This lets a structured logger take the fields one by
+// one instead of parsing them back out of a formatted string.
+
+use debug_visit::{FieldVisitor, VisitFields};
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+#[derive(CustomDebug)]
+#[debug(visit)]
+pub struct Origin {
+    #[debug(rename = "ip")]
+    address: &'static str,
+    port: u16,
+}
+
+#[derive(CustomDebug)]
+#[debug(visit, max_items = 2)]
+pub struct Request<B> {
+    method: &'static str,
+    #[debug(redact)]
+    token: String,
+    #[debug(skip)]
+    buffer: Vec<u8>,
+    #[debug(flatten)]
+    origin: Origin,
+    headers: Vec<&'static str>,
+    body: B,
+}
+
+#[derive(CustomDebug)]
+#[debug(visit)]
+pub enum Auth {
+    Anonymous,
+    Basic(&'static str, #[debug(redact)] String),
+    Session {
+        id: u64,
+        #[debug(skip)]
+        expires: u64,
+    },
+}
+
+// Collects the fields as `name=value`.
+#[derive(Default)]
+struct Tags(Vec<String>);
+
+impl FieldVisitor for Tags {
+    fn visit_field(&mut self, name: &str, value: &dyn Debug) {
+        self.0.push(format!("{}={:?}", name, value));
+    }
+}
+
+fn tags(value: &dyn VisitFields) -> Vec<String> {
+    let mut tags = Tags::default();
+    value.visit_fields(&mut tags);
+    tags.0
+}
+
+fn main() {
+    let request = Request {
+        method: "GET",
+        token: "hunter2".to_owned(),
+        buffer: vec![0; 64],
+        origin: Origin {
+            address: "10.0.0.1",
+            port: 443,
+        },
+        headers: vec!["accept", "host", "user-agent"],
+        body: (),
+    };
+    let debug = format!("{:?}", request);
+    let expected = r#"Request { method: "GET", token: [redacted], ip: "10.0.0.1", port: 443, headers: ["accept", "host", ... (1 more)], body: () }"#;
+    assert_eq!(debug, expected);
+    let expected = [
+        r#"method="GET""#,
+        "token=[redacted]",
+        r#"ip="10.0.0.1""#,
+        "port=443",
+        r#"headers=["accept", "host", ... (1 more)]"#,
+        "body=()",
+    ];
+    assert_eq!(tags(&request), expected);
+
+    let auth = Auth::Basic("admin", "secret".to_owned());
+    assert_eq!(format!("{:?}", auth), r#"Basic("admin", [redacted])"#);
+    assert_eq!(tags(&auth), [r#"0="admin""#, "1=[redacted]"]);
+
+    let auth = Auth::Session { id: 7, expires: 60 };
+    assert_eq!(format!("{:?}", auth), "Session { id: 7 }");
+    assert_eq!(tags(&auth), ["id=7"]);
+
+    assert!(tags(&Auth::Anonymous).is_empty());
+
+    // Any closure taking a name and a value is a visitor too.
+    let mut names = vec![];
+    request.visit_fields(&mut |name: &str, _: &dyn Debug| names.push(name.to_owned()));
+    assert_eq!(names, ["method", "token", "ip", "port", "headers", "body"]);
+}
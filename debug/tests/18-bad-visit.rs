@@ -0,0 +1,21 @@
+// A skipped field takes no other option, and a redacted one none that changes
+// how its value is printed. `visit` goes on the type, and the only field of a
+// transparent struct cannot be skipped.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Session {
+    #[debug(skip, rename = "key")]
+    key: String,
+    #[debug(redact, max_len = 4)]
+    token: String,
+    #[debug(visit)]
+    user: u64,
+}
+
+#[derive(CustomDebug)]
+#[debug(transparent)]
+pub struct Secret(#[debug(skip)] String);
+
+fn main() {}
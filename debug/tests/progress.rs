@@ -1,12 +1,22 @@
 #[test]
 fn tests() {
     let t = trybuild::TestCases::new();
-    //t.pass("tests/01-parse.rs");
-    //t.pass("tests/02-impl-debug.rs");
-    //t.pass("tests/03-custom-format.rs");
-    //t.pass("tests/04-type-parameter.rs");
-    //t.pass("tests/05-phantom-data.rs");
-    //t.pass("tests/06-bound-trouble.rs");
-    //t.pass("tests/07-associated-type.rs");
-    //t.pass("tests/08-escape-hatch.rs");
+    t.pass("tests/01-parse.rs");
+    t.pass("tests/02-impl-debug.rs");
+    t.pass("tests/03-custom-format.rs");
+    t.pass("tests/04-type-parameter.rs");
+    t.pass("tests/05-phantom-data.rs");
+    t.pass("tests/06-bound-trouble.rs");
+    t.pass("tests/07-associated-type.rs");
+    t.pass("tests/08-escape-hatch.rs");
+    t.pass("tests/09-with-formatter.rs");
+    t.compile_fail("tests/10-bad-options.rs");
+    t.pass("tests/11-truncation.rs");
+    t.pass("tests/12-display.rs");
+    t.compile_fail("tests/13-unknown-field.rs");
+    t.compile_fail("tests/14-bad-display.rs");
+    t.pass("tests/15-rename-transparent-flatten.rs");
+    t.compile_fail("tests/16-bad-layout-options.rs");
+    t.pass("tests/17-visit.rs");
+    t.compile_fail("tests/18-bad-visit.rs");
 }
@@ -0,0 +1,27 @@
+// The traits behind #[debug(visit)]. A proc-macro crate cannot export traits,
+// so they live here, and a crate deriving CustomDebug with `visit` depends on
+// this one too.
+
+use core::fmt::Debug;
+
+// Receives the fields of a value one at a time, under the names and with the
+// values its `Debug` output would show them.
+pub trait FieldVisitor {
+    fn visit_field(&mut self, name: &str, value: &dyn Debug);
+}
+
+impl<F> FieldVisitor for F
+where
+    F: FnMut(&str, &dyn Debug),
+{
+    fn visit_field(&mut self, name: &str, value: &dyn Debug) {
+        self(name, value)
+    }
+}
+
+// Implemented by #[debug(visit)]. Skipped fields are left out, redacted ones
+// are visited with a placeholder, and flattened ones are visited through the
+// fields of their own struct.
+pub trait VisitFields {
+    fn visit_fields(&self, visitor: &mut dyn FieldVisitor);
+}
@@ -0,0 +1,49 @@
+// Expansion time for very large ranges. Proc macro crates cannot export
+// anything but macros, so this drives `SeqInput` directly through
+// proc_macro2 instead of living in a `benches/` target. Debug builds are too
+// slow for the time limit to mean anything, so the tests only run in release.
+//
+//     $ cargo test --release --lib -- --nocapture
+
+use crate::SeqInput;
+use std::time::{Duration, Instant};
+
+const ITERATIONS: usize = 65536;
+
+fn expand(input: &str) -> Duration {
+    let start = Instant::now();
+    let seq_input: SeqInput = syn::parse_str(input).unwrap();
+    let output = seq_input.build().unwrap();
+    let elapsed = start.elapsed();
+    assert!(!output.is_empty());
+    elapsed
+}
+
+#[test]
+#[cfg_attr(debug_assertions, ignore = "only timed in release builds")]
+fn lookup_table_65536() {
+    let elapsed = expand(
+        "N in 0..65536 {
+            static TABLE: [u32; 65536] = [#(#{N * 2 + 1},)*];
+            #(
+                fn entry_~{N:04X}() -> u32 {
+                    TABLE[N]
+                }
+            )*
+        }",
+    );
+    println!("expanded {} iterations in {:?}", ITERATIONS, elapsed);
+    assert!(elapsed < Duration::from_secs(1), "took {:?}", elapsed);
+}
+
+#[test]
+#[cfg_attr(debug_assertions, ignore = "only timed in release builds")]
+fn whole_body_65536() {
+    let elapsed = expand(
+        "N in 0..65536 {
+            const _: u32 = N + (N << 1) - N % 7;
+        }",
+    );
+    println!("expanded {} iterations in {:?}", ITERATIONS, elapsed);
+    assert!(elapsed < Duration::from_secs(1), "took {:?}", elapsed);
+}
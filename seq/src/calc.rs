@@ -0,0 +1,193 @@
+use crate::int::Int;
+use crate::{checked, Value};
+use proc_macro2::{Ident, TokenStream as TokenStream2};
+use quote::ToTokens;
+use syn::{
+    BinOp, Error, Expr, ExprBinary, ExprGroup, ExprLit, ExprParen, ExprUnary, Lit, Result, UnOp,
+};
+
+// Maps a loop variable bound at some point of the body to its slot.
+pub(crate) type Resolve<'a> = &'a dyn Fn(&Ident) -> Option<usize>;
+
+// Integer arithmetic from a range bound, `#{...}` or `#if`, compiled once so
+// that evaluating it for every iteration does not have to resolve loop
+// variables by name again.
+pub(crate) enum Calc {
+    Int(Int),
+    Var(usize, Ident),
+    Neg(Box<Calc>, TokenStream2),
+    Binary(Box<Calc>, BinOp, Box<Calc>, TokenStream2),
+}
+
+// Condition of `#if`, which compares integer expressions and combines the
+// results with `&&`, `||` and `!`.
+pub(crate) enum Cond {
+    Bool(bool),
+    Not(Box<Cond>),
+    And(Box<Cond>, Box<Cond>),
+    Or(Box<Cond>, Box<Cond>),
+    Compare(Calc, BinOp, Calc),
+}
+
+// Evaluates a range bound, which may only use literals. Proc macros cannot see
+// `const` items.
+pub(crate) fn eval_bound(expr: &Expr) -> Result<Int> {
+    Calc::compile(expr, None)?.eval(&[])
+}
+
+impl Calc {
+    // `resolve` is `None` for range bounds, which cannot refer to loop
+    // variables.
+    pub(crate) fn compile(expr: &Expr, resolve: Option<Resolve>) -> Result<Self> {
+        match expr {
+            Expr::Lit(ExprLit {
+                lit: Lit::Int(lit), ..
+            }) => Ok(Self::Int(Int::from_lit(lit)?)),
+            Expr::Paren(ExprParen { expr, .. }) | Expr::Group(ExprGroup { expr, .. }) => {
+                Self::compile(expr, resolve)
+            }
+            Expr::Unary(
+                unary @ ExprUnary {
+                    op: UnOp::Neg(_),
+                    expr,
+                    ..
+                },
+            ) => Ok(Self::Neg(
+                Box::new(Self::compile(expr, resolve)?),
+                unary.to_token_stream(),
+            )),
+            Expr::Path(path) if resolve.is_some() => {
+                let var = path.path.get_ident().and_then(|ident| {
+                    let slot = resolve.unwrap()(ident)?;
+                    Some(Self::Var(slot, ident.clone()))
+                });
+                var.ok_or_else(|| {
+                    Error::new_spanned(
+                        path,
+                        "expected an integer literal or a loop variable bound here",
+                    )
+                })
+            }
+            Expr::Binary(binary) => Ok(Self::Binary(
+                Box::new(Self::compile(&binary.left, resolve)?),
+                binary.op,
+                Box::new(Self::compile(&binary.right, resolve)?),
+                binary.to_token_stream(),
+            )),
+            _ if resolve.is_some() => Err(Error::new_spanned(
+                expr,
+                "expected integer arithmetic on literals and loop variables",
+            )),
+            _ => Err(Error::new_spanned(
+                expr,
+                "seq! bound must be an integer literal or arithmetic on literals",
+            )),
+        }
+    }
+
+    pub(crate) fn eval(&self, slots: &[Option<&Value>]) -> Result<Int> {
+        match self {
+            Self::Int(value) => Ok(*value),
+            Self::Var(slot, ident) => match slots[*slot] {
+                Some(Value::Int(value)) => Ok(*value),
+                _ => Err(Error::new(
+                    ident.span(),
+                    "only integer loop variables can be used in arithmetic",
+                )),
+            },
+            Self::Neg(calc, tokens) => checked(calc.eval(slots)?.neg(), tokens),
+            Self::Binary(left, op, right, tokens) => {
+                let left = left.eval(slots)?;
+                let right = right.eval(slots)?;
+                if !matches!(op, BinOp::Shl(_) | BinOp::Shr(_)) {
+                    if let (Some(left_ty), Some(right_ty)) = (left.ty, right.ty) {
+                        if left_ty != right_ty {
+                            return Err(Error::new_spanned(
+                                tokens,
+                                format!(
+                                    "mismatched integer types `{}` and `{}`",
+                                    left_ty.name, right_ty.name
+                                ),
+                            ));
+                        }
+                    }
+                }
+                let left = left.with_type(left.ty.or(right.ty));
+                match left.binary(op, right) {
+                    Ok(value) => checked(value, tokens),
+                    Err(message) => Err(Error::new_spanned(tokens, message)),
+                }
+            }
+        }
+    }
+}
+
+impl Cond {
+    pub(crate) fn compile(expr: &Expr, resolve: Resolve) -> Result<Self> {
+        match expr {
+            Expr::Lit(ExprLit {
+                lit: Lit::Bool(lit),
+                ..
+            }) => Ok(Self::Bool(lit.value)),
+            Expr::Paren(ExprParen { expr, .. }) | Expr::Group(ExprGroup { expr, .. }) => {
+                Self::compile(expr, resolve)
+            }
+            Expr::Unary(ExprUnary {
+                op: UnOp::Not(_),
+                expr,
+                ..
+            }) => Ok(Self::Not(Box::new(Self::compile(expr, resolve)?))),
+            Expr::Binary(ExprBinary {
+                left, op, right, ..
+            }) => match op {
+                BinOp::And(_) => Ok(Self::And(
+                    Box::new(Self::compile(left, resolve)?),
+                    Box::new(Self::compile(right, resolve)?),
+                )),
+                BinOp::Or(_) => Ok(Self::Or(
+                    Box::new(Self::compile(left, resolve)?),
+                    Box::new(Self::compile(right, resolve)?),
+                )),
+                BinOp::Eq(_)
+                | BinOp::Ne(_)
+                | BinOp::Lt(_)
+                | BinOp::Le(_)
+                | BinOp::Gt(_)
+                | BinOp::Ge(_) => Ok(Self::Compare(
+                    Calc::compile(left, Some(resolve))?,
+                    *op,
+                    Calc::compile(right, Some(resolve))?,
+                )),
+                _ => Err(Error::new_spanned(
+                    expr,
+                    "expected a comparison such as `N % 2 == 0`",
+                )),
+            },
+            _ => Err(Error::new_spanned(
+                expr,
+                "expected a comparison such as `N % 2 == 0`",
+            )),
+        }
+    }
+
+    pub(crate) fn eval(&self, slots: &[Option<&Value>]) -> Result<bool> {
+        match self {
+            Self::Bool(value) => Ok(*value),
+            Self::Not(cond) => Ok(!cond.eval(slots)?),
+            Self::And(left, right) => Ok(left.eval(slots)? && right.eval(slots)?),
+            Self::Or(left, right) => Ok(left.eval(slots)? || right.eval(slots)?),
+            Self::Compare(left, op, right) => {
+                let left = left.eval(slots)?;
+                let right = right.eval(slots)?;
+                Ok(match op {
+                    BinOp::Eq(_) => left == right,
+                    BinOp::Ne(_) => left != right,
+                    BinOp::Lt(_) => left < right,
+                    BinOp::Le(_) => left <= right,
+                    BinOp::Gt(_) => left > right,
+                    _ => left >= right,
+                })
+            }
+        }
+    }
+}
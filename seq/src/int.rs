@@ -0,0 +1,217 @@
+use proc_macro2::{Delimiter, Group, Literal, Span, TokenTree};
+use std::cmp::Ordering;
+use syn::{BinOp, Error, LitInt, Result};
+
+// Integer value of a loop variable or of arithmetic inside seq!. Stored as
+// sign and magnitude so that both the full `i128` and `u128` ranges fit.
+#[derive(Clone, Copy)]
+pub(crate) struct Int {
+    negative: bool,
+    magnitude: u128,
+    // Type suffix written on the range bounds, kept on every substituted
+    // literal.
+    pub(crate) ty: Option<IntType>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) struct IntType {
+    pub(crate) name: &'static str,
+    bits: u32,
+    signed: bool,
+}
+
+const INT_TYPES: [IntType; 12] = [
+    IntType::new("i8", 8, true),
+    IntType::new("i16", 16, true),
+    IntType::new("i32", 32, true),
+    IntType::new("i64", 64, true),
+    IntType::new("i128", 128, true),
+    IntType::new("isize", 64, true),
+    IntType::new("u8", 8, false),
+    IntType::new("u16", 16, false),
+    IntType::new("u32", 32, false),
+    IntType::new("u64", 64, false),
+    IntType::new("u128", 128, false),
+    IntType::new("usize", 64, false),
+];
+
+impl IntType {
+    const fn new(name: &'static str, bits: u32, signed: bool) -> Self {
+        Self { name, bits, signed }
+    }
+
+    fn from_suffix(suffix: &str) -> Option<Self> {
+        INT_TYPES.iter().copied().find(|ty| ty.name == suffix)
+    }
+
+    fn contains(self, value: &Int) -> bool {
+        match (self.signed, value.negative) {
+            (false, true) => false,
+            (false, false) => self.bits == 128 || value.magnitude >> self.bits == 0,
+            (true, false) => value.magnitude >> (self.bits - 1) == 0,
+            (true, true) => value.magnitude <= 1 << (self.bits - 1),
+        }
+    }
+}
+
+impl Int {
+    pub(crate) fn new(negative: bool, magnitude: u128, ty: Option<IntType>) -> Self {
+        Self {
+            negative: negative && magnitude != 0,
+            magnitude,
+            ty,
+        }
+    }
+
+    pub(crate) fn from_lit(lit: &LitInt) -> Result<Self> {
+        let magnitude = lit.base10_parse()?;
+        let ty = match lit.suffix() {
+            "" => None,
+            suffix => match IntType::from_suffix(suffix) {
+                Some(ty) => Some(ty),
+                None => {
+                    return Err(Error::new(
+                        lit.span(),
+                        format!("unsupported integer suffix `{}`", suffix),
+                    ))
+                }
+            },
+        };
+        Ok(Self::new(false, magnitude, ty))
+    }
+
+    pub(crate) fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub(crate) fn magnitude(&self) -> u128 {
+        self.magnitude
+    }
+
+    // A negative value is a `-` followed by a literal, so it is wrapped in
+    // parentheses to keep `N.abs()` or `N as u8` applying to all of it.
+    pub(crate) fn to_token(self, span: Span) -> TokenTree {
+        let mut literal = match (self.negative, self.ty) {
+            (false, None) => Literal::u128_unsuffixed(self.magnitude),
+            (_, ty) => {
+                let suffix = ty.map_or("", |ty| ty.name);
+                format!("{}{}", self, suffix).parse().unwrap()
+            }
+        };
+        literal.set_span(span);
+        if !self.negative {
+            return TokenTree::Literal(literal);
+        }
+        let mut group = Group::new(Delimiter::Parenthesis, TokenTree::Literal(literal).into());
+        group.set_span(span);
+        TokenTree::Group(group)
+    }
+
+    pub(crate) fn fits(&self) -> bool {
+        self.ty.is_none_or(|ty| ty.contains(self))
+    }
+
+    pub(crate) fn with_type(self, ty: Option<IntType>) -> Self {
+        Self { ty, ..self }
+    }
+
+    pub(crate) fn neg(self) -> Self {
+        Self::new(!self.negative, self.magnitude, self.ty)
+    }
+
+    fn add(self, other: Self) -> Option<Self> {
+        let (negative, magnitude) = if self.negative == other.negative {
+            (self.negative, self.magnitude.checked_add(other.magnitude)?)
+        } else if self.magnitude >= other.magnitude {
+            (self.negative, self.magnitude - other.magnitude)
+        } else {
+            (other.negative, other.magnitude - self.magnitude)
+        };
+        Some(Self::new(negative, magnitude, self.ty))
+    }
+
+    // Applies a binary operator with Rust's semantics for signed integers.
+    // Bitwise operators and shifts are only supported on non-negative values.
+    pub(crate) fn binary(self, op: &BinOp, other: Self) -> std::result::Result<Self, &'static str> {
+        const OVERFLOW: &str = "overflow evaluating expression";
+        let sign = self.negative != other.negative;
+        let (negative, magnitude) = match op {
+            BinOp::Add(_) => return self.add(other).ok_or(OVERFLOW),
+            BinOp::Sub(_) => return self.add(other.neg()).ok_or(OVERFLOW),
+            BinOp::Mul(_) => (
+                sign,
+                self.magnitude
+                    .checked_mul(other.magnitude)
+                    .ok_or(OVERFLOW)?,
+            ),
+            BinOp::Div(_) | BinOp::Rem(_) if other.magnitude == 0 => {
+                return Err("division by zero")
+            }
+            BinOp::Div(_) => (sign, self.magnitude / other.magnitude),
+            BinOp::Rem(_) => (self.negative, self.magnitude % other.magnitude),
+            BinOp::Shl(_)
+            | BinOp::Shr(_)
+            | BinOp::BitAnd(_)
+            | BinOp::BitOr(_)
+            | BinOp::BitXor(_)
+                if self.negative || other.negative =>
+            {
+                return Err("bitwise operators and shifts need non-negative operands");
+            }
+            BinOp::Shl(_) | BinOp::Shr(_) if other.magnitude >= 128 => return Err(OVERFLOW),
+            BinOp::Shl(_) => {
+                let shifted = self.magnitude << other.magnitude;
+                if shifted >> other.magnitude != self.magnitude {
+                    return Err(OVERFLOW);
+                }
+                (false, shifted)
+            }
+            BinOp::Shr(_) => (false, self.magnitude >> other.magnitude),
+            BinOp::BitAnd(_) => (false, self.magnitude & other.magnitude),
+            BinOp::BitOr(_) => (false, self.magnitude | other.magnitude),
+            BinOp::BitXor(_) => (false, self.magnitude ^ other.magnitude),
+            _ => return Err("unsupported operator"),
+        };
+        Ok(Self::new(negative, magnitude, self.ty))
+    }
+}
+
+impl From<usize> for Int {
+    fn from(value: usize) -> Self {
+        Self::new(false, value as u128, None)
+    }
+}
+
+impl std::fmt::Display for Int {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.negative {
+            f.write_str("-")?;
+        }
+        write!(f, "{}", self.magnitude)
+    }
+}
+
+impl PartialEq for Int {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Int {}
+
+impl PartialOrd for Int {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Int {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, false) => self.magnitude.cmp(&other.magnitude),
+            (true, true) => other.magnitude.cmp(&self.magnitude),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
@@ -1,8 +1,428 @@
+mod calc;
+mod int;
+mod template;
+
+use crate::calc::eval_bound;
+use crate::int::Int;
+use crate::template::Template;
 use proc_macro::TokenStream;
+use proc_macro2::{Ident, Literal, Span, TokenStream as TokenStream2, TokenTree};
+use quote::ToTokens;
+use std::cmp::Ordering;
+use syn::{
+    braced, bracketed, parenthesized,
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+    token::{Bracket, Paren},
+    Attribute, BinOp, Error, Expr, ExprGroup, ExprLit, ExprParen, ExprRange, Lit, LitInt, Meta,
+    RangeLimits, Result, Token, Type,
+};
+
+// How many times the body may be repeated unless raised with
+// `#![max_iterations = N]`, so that a mistyped bound is reported instead of
+// expanding for minutes.
+const DEFAULT_MAX_ITERATIONS: usize = 100_000;
 
 #[proc_macro]
 pub fn seq(input: TokenStream) -> TokenStream {
-    let _ = input;
+    let seq_input = parse_macro_input!(input as SeqInput);
+    match seq_input.build() {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.into_compile_error().into(),
+    }
+}
+
+struct SeqInput {
+    loops: Vec<Loop>,
+    body: TokenStream2,
+    max_iterations: usize,
+}
+
+struct Loop {
+    // Bound to the position of each value by `(i, T) in enumerate ...`.
+    index: Option<Ident>,
+    var: Ident,
+    values: Vec<Value>,
+    // Values of `index`, empty without `enumerate`.
+    positions: Vec<Value>,
+}
+
+enum Value {
+    Int(Int),
+    Char(char),
+    // An element of an explicit list, such as a type or a string literal,
+    // along with what it contributes when pasted into an identifier.
+    Tokens(TokenStream2, Option<String>),
+}
+
+impl Parse for SeqInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut max_iterations = DEFAULT_MAX_ITERATIONS;
+        for attr in input.call(Attribute::parse_inner)? {
+            max_iterations = parse_max_iterations(&attr)?;
+        }
+        let mut loops = vec![Loop::parse(input, max_iterations)?];
+        while input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+            let next = Loop::parse(input, max_iterations)?;
+            for var in next.vars() {
+                if loops.iter().any(|seen| seen.declares(var)) {
+                    return Err(Error::new(
+                        var.span(),
+                        format!("loop variable `{}` is declared twice", var),
+                    ));
+                }
+            }
+            loops.push(next);
+        }
+        let content;
+        braced!(content in input);
+        let body: TokenStream2 = content.parse()?;
+        Ok(Self {
+            loops,
+            body,
+            max_iterations,
+        })
+    }
+}
+
+// `#![max_iterations = N]` at the start of the input.
+fn parse_max_iterations(attr: &Attribute) -> Result<usize> {
+    match &attr.meta {
+        Meta::NameValue(meta) if meta.path.is_ident("max_iterations") => match &meta.value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Int(lit), ..
+            }) => lit.base10_parse(),
+            value => Err(Error::new_spanned(value, "expected an integer literal")),
+        },
+        _ => Err(Error::new_spanned(
+            attr,
+            "unsupported seq! option; expected `#![max_iterations = N]`",
+        )),
+    }
+}
+
+impl Loop {
+    // Not a `Parse` impl since ranges are checked against the iteration limit
+    // before they are materialized.
+    fn parse(input: ParseStream, max_iterations: usize) -> Result<Self> {
+        let (index, var) = if input.peek(Paren) {
+            let content;
+            parenthesized!(content in input);
+            let index: Ident = content.parse()?;
+            content.parse::<Token![,]>()?;
+            let var: Ident = content.parse()?;
+            (Some(index), var)
+        } else {
+            (None, input.parse()?)
+        };
+        input.parse::<Token![in]>()?;
+        let enumerate = matches!(input.cursor().ident(), Some((ident, _)) if ident == "enumerate");
+        if enumerate {
+            input.parse::<Ident>()?;
+        }
+        match (&index, enumerate) {
+            (Some(index), false) => {
+                return Err(Error::new(
+                    index.span(),
+                    "a pair of loop variables requires `in enumerate ...`",
+                ))
+            }
+            (None, true) => {
+                return Err(Error::new(
+                    var.span(),
+                    "expected `(index, var)` before `in enumerate`",
+                ))
+            }
+            _ => {}
+        }
+        let values = if input.peek(Bracket) {
+            let content;
+            let bracket = bracketed!(content in input);
+            let items = Punctuated::<ListItem, Token![,]>::parse_terminated(&content)?;
+            if items.is_empty() {
+                return Err(Error::new(bracket.span.join(), "list of values is empty"));
+            }
+            if items.len() > max_iterations {
+                return Err(Error::new(
+                    bracket.span.join(),
+                    too_many_values(max_iterations),
+                ));
+            }
+            items.into_iter().map(|item| item.0).collect()
+        } else {
+            let expr = Expr::parse_without_eager_brace(input)?;
+            range(&expr)?.values(&expr, max_iterations)?
+        };
+        let positions = match index {
+            Some(_) => (0..values.len())
+                .map(|i| Value::Int(Int::from(i)))
+                .collect(),
+            None => vec![],
+        };
+        Ok(Self {
+            index,
+            var,
+            values,
+            positions,
+        })
+    }
+
+    fn vars(&self) -> impl Iterator<Item = &Ident> {
+        self.index.iter().chain([&self.var])
+    }
+
+    fn declares(&self, ident: &Ident) -> bool {
+        self.vars().any(|var| var == ident)
+    }
+}
+
+// One element of an explicit list: an integer, string or char literal, or a
+// type.
+struct ListItem(Value);
+
+impl Parse for ListItem {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(Token![-]) && input.peek2(LitInt) {
+            input.parse::<Token![-]>()?;
+            let lit: LitInt = input.parse()?;
+            return Ok(Self(Value::Int(checked(Int::from_lit(&lit)?.neg(), &lit)?)));
+        }
+        if input.peek(Lit) {
+            return Ok(match input.parse()? {
+                Lit::Int(lit) => Self(Value::Int(checked(Int::from_lit(&lit)?, &lit)?)),
+                Lit::Char(lit) => Self(Value::Char(lit.value())),
+                Lit::Str(lit) => Self(Value::Tokens(lit.to_token_stream(), Some(lit.value()))),
+                lit => Self(Value::Tokens(lit.into_token_stream(), None)),
+            });
+        }
+        let ty: Type = input.parse()?;
+        let fragment = match &ty {
+            Type::Path(path) if path.qself.is_none() => {
+                path.path.get_ident().map(|ident| ident.to_string())
+            }
+            _ => None,
+        };
+        Ok(Self(Value::Tokens(ty.into_token_stream(), fragment)))
+    }
+}
+
+impl Value {
+    fn emit(&self, span: Span, out: &mut Vec<TokenTree>) {
+        match self {
+            Self::Int(value) => out.push(value.to_token(span)),
+            Self::Char(value) => {
+                let mut literal = Literal::character(*value);
+                literal.set_span(span);
+                out.push(TokenTree::Literal(literal));
+            }
+            Self::Tokens(tokens, _) => out.extend(tokens.clone()),
+        }
+    }
+
+    // The text this value contributes when pasted into an identifier with
+    // `prefix~N`. String and char literals contribute their contents.
+    fn paste_fragment(&self, var: &Ident) -> Result<String> {
+        match self {
+            Self::Int(value) => Ok(value.to_string()),
+            Self::Char(value) => Ok(value.to_string()),
+            Self::Tokens(_, Some(fragment)) => Ok(fragment.clone()),
+            Self::Tokens(tokens, None) => Err(Error::new(
+                var.span(),
+                format!("cannot paste `{}` into an identifier", tokens),
+            )),
+        }
+    }
+}
+
+// A range such as `0..2*8`, `(0..64).step_by(8)`, `(0..16).rev()` or
+// `'a'..='z'`. Integer ranges are kept as an arithmetic progression so that
+// their length is known without materializing them. Proc macros cannot see
+// `const` items, so only literals and arithmetic on them are accepted as
+// bounds.
+enum Range {
+    Ints { first: Int, step: Int, count: u128 },
+    Chars(Vec<char>),
+}
+
+fn range(expr: &Expr) -> Result<Range> {
+    match expr {
+        Expr::Range(range) => {
+            let (Some(start), Some(end)) = (&range.start, &range.end) else {
+                return Err(Error::new_spanned(range, "expected a bounded range"));
+            };
+            let inclusive = matches!(range.limits, RangeLimits::Closed(_));
+            match (char_lit(start), char_lit(end)) {
+                (Some(start_char), Some(end_char)) => {
+                    check_bounds(range, start_char.cmp(&end_char), inclusive)?;
+                    return Ok(Range::Chars(if inclusive {
+                        (start_char..=end_char).collect()
+                    } else {
+                        (start_char..end_char).collect()
+                    }));
+                }
+                (Some(_), None) => {
+                    return Err(Error::new_spanned(end, "expected a char bound"));
+                }
+                (None, Some(_)) => {
+                    return Err(Error::new_spanned(end, "expected an integer bound"));
+                }
+                (None, None) => {}
+            }
+            let start_value = eval_bound(start)?;
+            let end_value = eval_bound(end)?;
+            let ty = match (start_value.ty, end_value.ty) {
+                (Some(start_ty), Some(end_ty)) if start_ty != end_ty => {
+                    return Err(Error::new_spanned(
+                        end,
+                        format!(
+                            "mismatched range bound types `{}` and `{}`",
+                            start_ty.name, end_ty.name
+                        ),
+                    ));
+                }
+                (start_ty, end_ty) => start_ty.or(end_ty),
+            };
+            let start_value = checked(start_value.with_type(ty), start)?;
+            let end_value = checked(end_value.with_type(ty), end)?;
+            check_bounds(range, start_value.cmp(&end_value), inclusive)?;
+            // A length beyond `u128` is over any limit anyway.
+            let count = match end_value.binary(&BinOp::Sub(Default::default()), start_value) {
+                Ok(distance) => distance.magnitude().saturating_add(inclusive as u128),
+                Err(_) => u128::MAX,
+            };
+            Ok(Range::Ints {
+                first: start_value,
+                step: Int::new(false, 1, ty),
+                count,
+            })
+        }
+        Expr::Paren(ExprParen { expr, .. }) | Expr::Group(ExprGroup { expr, .. }) => range(expr),
+        Expr::MethodCall(call) => {
+            let receiver = range(&call.receiver)?;
+            let args = &call.args;
+            if call.method == "rev" && args.is_empty() {
+                Ok(match receiver {
+                    Range::Ints { first, step, count } => {
+                        let offset = Int::new(false, count - 1, step.ty);
+                        let last = step
+                            .binary(&BinOp::Mul(Default::default()), offset)
+                            .and_then(|offset| {
+                                first.binary(&BinOp::Add(Default::default()), offset)
+                            })
+                            .map_err(|message| Error::new_spanned(call, message))?;
+                        Range::Ints {
+                            first: last,
+                            step: step.neg(),
+                            count,
+                        }
+                    }
+                    Range::Chars(chars) => Range::Chars(chars.into_iter().rev().collect()),
+                })
+            } else if call.method == "step_by" && args.len() == 1 {
+                let by = eval_bound(&args[0])?;
+                if by.is_negative() || by.magnitude() == 0 {
+                    return Err(Error::new_spanned(&args[0], "step must be positive"));
+                }
+                Ok(match receiver {
+                    Range::Ints { first, step, count } => {
+                        let count = count.div_ceil(by.magnitude());
+                        // With a single value left the step is never taken and
+                        // may as well have overflowed.
+                        let step = match count {
+                            0 | 1 => step,
+                            _ => step
+                                .binary(&BinOp::Mul(Default::default()), by.with_type(step.ty))
+                                .map_err(|message| Error::new_spanned(call, message))?,
+                        };
+                        Range::Ints { first, step, count }
+                    }
+                    Range::Chars(chars) => {
+                        let by = usize::try_from(by.magnitude()).unwrap_or(usize::MAX);
+                        Range::Chars(chars.into_iter().step_by(by).collect())
+                    }
+                })
+            } else {
+                Err(Error::new_spanned(
+                    &call.method,
+                    "expected `.rev()` or `.step_by(n)`",
+                ))
+            }
+        }
+        _ => Err(Error::new_spanned(expr, "expected a range such as `0..8`")),
+    }
+}
+
+impl Range {
+    fn values(self, expr: &Expr, max_iterations: usize) -> Result<Vec<Value>> {
+        let count = match &self {
+            Range::Ints { count, .. } => *count,
+            Range::Chars(chars) => chars.len() as u128,
+        };
+        if count > max_iterations as u128 {
+            return Err(Error::new_spanned(expr, too_many_values(max_iterations)));
+        }
+        Ok(match self {
+            Range::Ints { first, step, count } => {
+                let add = BinOp::Add(Default::default());
+                std::iter::successors(Some(first), |value| value.binary(&add, step).ok())
+                    .take(count as usize)
+                    .map(Value::Int)
+                    .collect()
+            }
+            Range::Chars(chars) => chars.into_iter().map(Value::Char).collect(),
+        })
+    }
+}
 
-    unimplemented!()
+// Rejects ranges that would not yield anything, which is almost always a typo
+// in one of the bounds.
+fn check_bounds(range: &ExprRange, order: Ordering, inclusive: bool) -> Result<()> {
+    match order {
+        Ordering::Greater => Err(Error::new_spanned(
+            range,
+            "range start is greater than its end",
+        )),
+        Ordering::Equal if !inclusive => Err(Error::new_spanned(range, "range is empty")),
+        _ => Ok(()),
+    }
 }
+
+fn too_many_values(max_iterations: usize) -> String {
+    format!(
+        "more than {} values; raise the limit with `#![max_iterations = N]` if this is intended",
+        max_iterations
+    )
+}
+
+fn char_lit(expr: &Expr) -> Option<char> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Char(lit),
+            ..
+        }) => Some(lit.value()),
+        Expr::Paren(ExprParen { expr, .. }) | Expr::Group(ExprGroup { expr, .. }) => char_lit(expr),
+        _ => None,
+    }
+}
+
+// Rejects values that do not fit the integer type they are suffixed with.
+fn checked<T: ToTokens>(value: Int, tokens: T) -> Result<Int> {
+    match value.ty {
+        Some(ty) if !value.fits() => Err(Error::new_spanned(
+            tokens,
+            format!("`{}` is out of range for `{}`", value, ty.name),
+        )),
+        _ => Ok(value),
+    }
+}
+
+impl SeqInput {
+    fn build(&self) -> Result<TokenStream2> {
+        Template::compile(self)?.expand(self)
+    }
+}
+
+#[cfg(test)]
+mod bench;
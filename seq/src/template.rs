@@ -0,0 +1,704 @@
+use crate::calc::{Calc, Cond};
+use crate::{SeqInput, Value};
+use proc_macro2::{
+    Delimiter, Group, Ident, Punct, Spacing, Span, TokenStream as TokenStream2, TokenTree,
+};
+use syn::parse::{ParseStream, Parser};
+use syn::{braced, Error, Expr, Result};
+
+// The body of seq!, analysed once into the tokens that are copied as is and
+// the sites that change from one iteration to the next. Expanding the template
+// only visits those sites instead of re-walking the body's `TokenStream` for
+// every iteration.
+pub(crate) struct Template {
+    nodes: Vec<Node>,
+    // Loops repeating the whole body, which is the case when it has no
+    // `#(...)*` sections.
+    loops: Vec<usize>,
+}
+
+enum Node {
+    Token(TokenTree),
+    Group(Delimiter, Span, Vec<Node>),
+    // A loop variable, replaced by its value.
+    Var(usize, Span),
+    // `prefix~N~{M:spec}...`, pasted into a single identifier spanned like
+    // the prefix.
+    Paste(Ident, Vec<Paste>),
+    Section(Section),
+    // `#{expr}`, replaced by the integer literal it evaluates to.
+    Calc(Calc, Span),
+    // `#if cond { ... } else { ... }`
+    If(Cond, Vec<Node>, Vec<Node>),
+}
+
+struct Section {
+    loops: Vec<usize>,
+    // Emitted between repetitions, as in `#(...),*`.
+    separator: Option<Punct>,
+    body: Vec<Node>,
+}
+
+// One `~N` or `~{N:spec}` following an identifier.
+struct Paste {
+    slot: usize,
+    var: Ident,
+    format: Format,
+}
+
+enum Format {
+    Plain,
+    Upper,
+    Lower,
+    Snake,
+    Camel,
+    // `[0width][x|X|b|o]` for integers, with the tokens of the spec kept for
+    // errors on non-integer values.
+    Int {
+        width: usize,
+        radix: Option<char>,
+        spec: TokenStream2,
+    },
+}
+
+// The repeat section found at some position of the body, before compiling.
+struct SectionAt<'a> {
+    // `#N(...)*` repeats over the loop declaring `N` only, while `#(...)*`
+    // repeats over every loop not bound by an enclosing section.
+    var: Option<&'a Ident>,
+    body: &'a Group,
+    separator: Option<&'a Punct>,
+    // Number of tokens the section occupies in its parent stream.
+    len: usize,
+}
+
+impl Template {
+    pub(crate) fn compile(input: &SeqInput) -> Result<Self> {
+        let mut compiler = Compiler {
+            input,
+            bound: vec![false; input.loops.len()],
+            nested: 0,
+        };
+        if compiler.has_repeat_section(input.body.clone(), false) {
+            let nodes = compiler.compile(input.body.clone())?;
+            return Ok(Self {
+                nodes,
+                loops: vec![],
+            });
+        }
+        let loops: Vec<usize> = (0..input.loops.len()).collect();
+        compiler.bound.fill(true);
+        compiler.check_iterations()?;
+        let nodes = compiler.compile(input.body.clone())?;
+        Ok(Self { nodes, loops })
+    }
+
+    pub(crate) fn expand(&self, input: &SeqInput) -> Result<TokenStream2> {
+        let mut emitter = Emitter {
+            input,
+            slots: vec![None; input.loops.len() * 2],
+        };
+        let iterations: usize = self
+            .loops
+            .iter()
+            .map(|&l| input.loops[l].values.len())
+            .product();
+        let mut out = Vec::with_capacity(size_hint(&self.nodes, input).saturating_mul(iterations));
+        let mut first = true;
+        emitter.repeat(&self.loops, &self.nodes, None, &mut first, &mut out)?;
+        Ok(TokenStream2::from_iter(out))
+    }
+}
+
+// Number of tokens a single expansion of `nodes` produces at the top level,
+// counting every repetition of a section.
+fn size_hint(nodes: &[Node], input: &SeqInput) -> usize {
+    nodes
+        .iter()
+        .map(|node| match node {
+            Node::Section(section) => {
+                let iterations: usize = section
+                    .loops
+                    .iter()
+                    .map(|&l| input.loops[l].values.len())
+                    .product();
+                size_hint(&section.body, input).saturating_mul(iterations)
+            }
+            Node::If(_, then_branch, _) => size_hint(then_branch, input),
+            _ => 1,
+        })
+        .sum()
+}
+
+struct Compiler<'a> {
+    input: &'a SeqInput,
+    // Which loops are bound at the current point of the body.
+    bound: Vec<bool>,
+    // Depth of `seq!` invocations nested in the body. Inside one, only this
+    // macro's own variables are expanded and anything else, such as pastes of
+    // the inner macro's variables or its `#(...)*` sections, is left to it.
+    nested: usize,
+}
+
+impl Compiler<'_> {
+    // Slot of a loop variable bound at the current point of the body. Loop `l`
+    // keeps its enumerate index in slot `2 * l` and its value in `2 * l + 1`.
+    fn resolve(&self, ident: &Ident) -> Option<usize> {
+        self.input
+            .loops
+            .iter()
+            .enumerate()
+            .filter(|&(l, _)| self.bound[l])
+            .find_map(|(l, lp)| {
+                if lp.index.as_ref() == Some(ident) {
+                    Some(2 * l)
+                } else if lp.var == *ident {
+                    Some(2 * l + 1)
+                } else {
+                    None
+                }
+            })
+    }
+
+    // Rejects a point of the body that would be repeated more often than
+    // allowed, pointing at the loop that tips it over.
+    fn check_iterations(&self) -> Result<()> {
+        let max_iterations = self.input.max_iterations;
+        let mut iterations: usize = 1;
+        for (l, lp) in self.input.loops.iter().enumerate() {
+            if !self.bound[l] {
+                continue;
+            }
+            iterations = iterations.saturating_mul(lp.values.len());
+            if iterations > max_iterations {
+                return Err(Error::new(
+                    lp.var.span(),
+                    format!(
+                        "repeating over `{}` as well makes more than {} iterations; raise the \
+                         limit with `#![max_iterations = N]` if this is intended",
+                        lp.var, max_iterations
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn compile(&mut self, stream: TokenStream2) -> Result<Vec<Node>> {
+        let tokens: Vec<TokenTree> = stream.into_iter().collect();
+        let mut nodes = Vec::with_capacity(tokens.len());
+        let mut i = 0;
+        while i < tokens.len() {
+            let nested = self.nested > 0;
+            if let Some(section) = self.repeat_section_at(&tokens, i, nested) {
+                nodes.push(Node::Section(self.compile_section(&section)?));
+                i += section.len;
+                continue;
+            }
+            if !nested {
+                if let Some((node, len)) = self.compile_directive(&tokens, i)? {
+                    nodes.push(node);
+                    i += len;
+                    continue;
+                }
+            }
+            match &tokens[i] {
+                TokenTree::Group(group) => {
+                    let inner = is_seq_invocation(&tokens, i) as usize;
+                    self.nested += inner;
+                    let body = self.compile(group.stream());
+                    self.nested -= inner;
+                    nodes.push(Node::Group(group.delimiter(), group.span(), body?));
+                }
+                TokenTree::Ident(ident) => {
+                    if let Some(slot) = self.resolve(ident) {
+                        nodes.push(Node::Var(slot, ident.span()));
+                    } else {
+                        let mut pastes = vec![];
+                        while let Some(paste) = self.paste_at(&tokens, i + 1)? {
+                            pastes.push(paste);
+                            i += 2;
+                        }
+                        if pastes.is_empty() {
+                            nodes.push(Node::Token(tokens[i].clone()));
+                        } else {
+                            nodes.push(Node::Paste(ident.clone(), pastes));
+                        }
+                    }
+                }
+                TokenTree::Punct(tilde) if tilde.as_char() == '~' && !nested => {
+                    return Err(Error::new(
+                        tilde.span(),
+                        "`~` must follow the identifier to paste into, as in `prefix~N`",
+                    ));
+                }
+                tt => nodes.push(Node::Token(tt.clone())),
+            }
+            i += 1;
+        }
+        Ok(nodes)
+    }
+
+    fn compile_section(&mut self, section: &SectionAt) -> Result<Section> {
+        let loops: Vec<usize> = match section.var {
+            None => (0..self.input.loops.len())
+                .filter(|&l| !self.bound[l])
+                .collect(),
+            Some(var) => {
+                let l = self
+                    .input
+                    .loops
+                    .iter()
+                    .position(|lp| lp.declares(var))
+                    .unwrap();
+                if self.bound[l] {
+                    return Err(Error::new(
+                        var.span(),
+                        format!("`{}` is already bound by an enclosing section", var),
+                    ));
+                }
+                vec![l]
+            }
+        };
+        for &l in &loops {
+            self.bound[l] = true;
+        }
+        let body = self
+            .check_iterations()
+            .and_then(|()| self.compile(section.body.stream()));
+        for &l in &loops {
+            self.bound[l] = false;
+        }
+        Ok(Section {
+            loops,
+            separator: section.separator.cloned(),
+            body: body?,
+        })
+    }
+
+    // Compiles `#{expr}` and `#if cond { ... } else { ... }`, returning the
+    // number of tokens consumed, or `None` if there is no directive at `i`.
+    fn compile_directive(
+        &mut self,
+        tokens: &[TokenTree],
+        i: usize,
+    ) -> Result<Option<(Node, usize)>> {
+        let Some(TokenTree::Punct(pound)) = tokens.get(i) else {
+            return Ok(None);
+        };
+        if pound.as_char() != '#' {
+            return Ok(None);
+        }
+        let resolve = |ident: &Ident| self.resolve(ident);
+        match tokens.get(i + 1) {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+                // Parsing the group itself rather than its contents makes a
+                // missing expression point at the braces, not the macro call.
+                let expr = (|input: ParseStream| {
+                    let content;
+                    braced!(content in input);
+                    let expr: Expr = content.parse()?;
+                    if !content.is_empty() {
+                        return Err(content.error("unexpected token"));
+                    }
+                    Ok(expr)
+                })
+                .parse2(TokenTree::Group(group.clone()).into())?;
+                let calc = Calc::compile(&expr, Some(&resolve))?;
+                Ok(Some((Node::Calc(calc, group.span()), 2)))
+            }
+            Some(TokenTree::Ident(keyword)) if keyword == "if" => {
+                let then_branch =
+                    tokens
+                        .iter()
+                        .enumerate()
+                        .skip(i + 2)
+                        .find_map(|(j, tt)| match tt {
+                            TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => {
+                                Some((j, group))
+                            }
+                            _ => None,
+                        });
+                let Some((then_at, then_branch)) = then_branch else {
+                    return Err(Error::new(
+                        keyword.span(),
+                        "expected `{` after `#if` condition",
+                    ));
+                };
+                let condition: TokenStream2 = tokens[i + 2..then_at].iter().cloned().collect();
+                let condition: Expr = syn::parse2(condition)?;
+                let cond = Cond::compile(&condition, &resolve)?;
+                let else_branch = match (tokens.get(then_at + 1), tokens.get(then_at + 2)) {
+                    (Some(TokenTree::Ident(keyword)), Some(TokenTree::Group(group)))
+                        if keyword == "else" && group.delimiter() == Delimiter::Brace =>
+                    {
+                        Some(group)
+                    }
+                    _ => None,
+                };
+                let then_nodes = self.compile(then_branch.stream())?;
+                let else_nodes = match else_branch {
+                    Some(group) => self.compile(group.stream())?,
+                    None => vec![],
+                };
+                let len = then_at - i + 1;
+                let len = if else_branch.is_some() { len + 2 } else { len };
+                Ok(Some((Node::If(cond, then_nodes, else_nodes), len)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn has_repeat_section(&self, stream: TokenStream2, nested: bool) -> bool {
+        let tokens: Vec<TokenTree> = stream.into_iter().collect();
+        tokens.iter().enumerate().any(|(i, tt)| match tt {
+            TokenTree::Group(group) => {
+                self.has_repeat_section(group.stream(), nested || is_seq_invocation(&tokens, i))
+            }
+            _ => self.repeat_section_at(&tokens, i, nested).is_some(),
+        })
+    }
+
+    // Inside a nested `seq!` only sections naming one of this macro's
+    // variables, as in `#N(...)*`, are this macro's.
+    fn repeat_section_at<'a>(
+        &self,
+        tokens: &'a [TokenTree],
+        i: usize,
+        nested: bool,
+    ) -> Option<SectionAt<'a>> {
+        let TokenTree::Punct(pound) = tokens.get(i)? else {
+            return None;
+        };
+        if pound.as_char() != '#' {
+            return None;
+        }
+        let var = match tokens.get(i + 1)? {
+            TokenTree::Ident(var) if self.input.loops.iter().any(|l| l.declares(var)) => Some(var),
+            _ => None,
+        };
+        if nested && var.is_none() {
+            return None;
+        }
+        let start = i + 1 + var.is_some() as usize;
+        let (body, separator, len) = section_body_at(tokens, start)?;
+        Some(SectionAt {
+            var,
+            body,
+            separator,
+            len: start - i + len,
+        })
+    }
+
+    fn paste_at(&self, tokens: &[TokenTree], i: usize) -> Result<Option<Paste>> {
+        let Some(TokenTree::Punct(tilde)) = tokens.get(i) else {
+            return Ok(None);
+        };
+        if tilde.as_char() != '~' {
+            return Ok(None);
+        }
+        let (var, spec) = match tokens.get(i + 1) {
+            Some(TokenTree::Ident(var)) => (var.clone(), None),
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+                let mut inner = group.stream().into_iter();
+                match (inner.next(), inner.next()) {
+                    (Some(TokenTree::Ident(var)), None) => (var, None),
+                    (Some(TokenTree::Ident(var)), Some(TokenTree::Punct(colon)))
+                        if colon.as_char() == ':' =>
+                    {
+                        (var, Some(inner.collect::<TokenStream2>()))
+                    }
+                    _ => {
+                        return Err(Error::new(
+                            group.span(),
+                            "expected `{N}` or `{N:spec}` after `~`",
+                        ))
+                    }
+                }
+            }
+            _ => {
+                return Err(Error::new(
+                    tilde.span(),
+                    "expected a loop variable after `~`",
+                ))
+            }
+        };
+        let Some(slot) = self.resolve(&var) else {
+            if self.nested > 0 && !self.input.loops.iter().any(|lp| lp.declares(&var)) {
+                return Ok(None);
+            }
+            let message = if self.input.loops.iter().any(|lp| lp.declares(&var)) {
+                format!(
+                    "`{}` is only bound inside a `#(...)*` section repeating it",
+                    var
+                )
+            } else {
+                format!("unknown loop variable `{}`", var)
+            };
+            return Err(Error::new(var.span(), message));
+        };
+        let format = match spec {
+            None => Format::Plain,
+            Some(spec) => Format::parse(spec)?,
+        };
+        Ok(Some(Paste { slot, var, format }))
+    }
+}
+
+// Whether the group at `i` holds the input of a `seq!` invocation.
+fn is_seq_invocation(tokens: &[TokenTree], i: usize) -> bool {
+    i >= 2
+        && matches!(
+            (&tokens[i - 2], &tokens[i - 1]),
+            (TokenTree::Ident(name), TokenTree::Punct(bang)) if name == "seq" && bang.as_char() == '!'
+        )
+}
+
+// Matches the `(...)*` half of a repeat section, optionally with a single
+// separator punctuation before the `*` as in `(...),*`.
+fn section_body_at(tokens: &[TokenTree], i: usize) -> Option<(&Group, Option<&Punct>, usize)> {
+    let TokenTree::Group(group) = tokens.get(i)? else {
+        return None;
+    };
+    if group.delimiter() != Delimiter::Parenthesis {
+        return None;
+    }
+    match (tokens.get(i + 1), tokens.get(i + 2)) {
+        (Some(TokenTree::Punct(star)), _) if star.as_char() == '*' => Some((group, None, 2)),
+        (Some(TokenTree::Punct(separator)), Some(TokenTree::Punct(star)))
+            if star.as_char() == '*' =>
+        {
+            Some((group, Some(separator), 3))
+        }
+        _ => None,
+    }
+}
+
+struct Emitter<'a> {
+    input: &'a SeqInput,
+    slots: Vec<Option<&'a Value>>,
+}
+
+impl<'a> Emitter<'a> {
+    // Emits `body` once for every combination of values of `loops`, in order.
+    fn repeat(
+        &mut self,
+        loops: &[usize],
+        body: &[Node],
+        separator: Option<&Punct>,
+        first: &mut bool,
+        out: &mut Vec<TokenTree>,
+    ) -> Result<()> {
+        let Some((&l, rest)) = loops.split_first() else {
+            let start = out.len();
+            if let (false, Some(separator)) = (*first, separator) {
+                let mut punct = Punct::new(separator.as_char(), Spacing::Alone);
+                punct.set_span(separator.span());
+                out.push(TokenTree::Punct(punct));
+            }
+            let body_start = out.len();
+            self.emit(body, out)?;
+            // An iteration that produced nothing, such as one whose `#if` was
+            // false, is not separated from the others.
+            if out.len() == body_start {
+                out.truncate(start);
+            } else {
+                *first = false;
+            }
+            return Ok(());
+        };
+        let lp = &self.input.loops[l];
+        for position in 0..lp.values.len() {
+            self.slots[2 * l] = lp.positions.get(position);
+            self.slots[2 * l + 1] = Some(&lp.values[position]);
+            self.repeat(rest, body, separator, first, out)?;
+        }
+        Ok(())
+    }
+
+    fn emit(&mut self, nodes: &[Node], out: &mut Vec<TokenTree>) -> Result<()> {
+        for node in nodes {
+            match node {
+                Node::Token(tt) => out.push(tt.clone()),
+                Node::Group(delimiter, span, body) => {
+                    let mut inner = Vec::with_capacity(size_hint(body, self.input));
+                    self.emit(body, &mut inner)?;
+                    let mut group = Group::new(*delimiter, TokenStream2::from_iter(inner));
+                    group.set_span(*span);
+                    out.push(TokenTree::Group(group));
+                }
+                Node::Var(slot, span) => self.slots[*slot].unwrap().emit(*span, out),
+                Node::Paste(prefix, pastes) => {
+                    let mut pasted = prefix.to_string();
+                    for paste in pastes {
+                        pasted.push_str(&paste.fragment(self.slots[paste.slot].unwrap())?);
+                    }
+                    if !is_ident(&pasted) {
+                        return Err(Error::new(
+                            prefix.span(),
+                            format!("`{}` is not a valid identifier", pasted),
+                        ));
+                    }
+                    out.push(TokenTree::Ident(Ident::new(&pasted, prefix.span())));
+                }
+                Node::Section(section) => {
+                    let mut first = true;
+                    let separator = section.separator.as_ref();
+                    self.repeat(&section.loops, &section.body, separator, &mut first, out)?;
+                }
+                Node::Calc(calc, span) => {
+                    let value = calc.eval(&self.slots)?;
+                    out.push(value.to_token(*span));
+                }
+                Node::If(cond, then_branch, else_branch) => {
+                    if cond.eval(&self.slots)? {
+                        self.emit(then_branch, out)?;
+                    } else {
+                        self.emit(else_branch, out)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// Checks that pasting produced something `Ident::new` accepts, following the
+// same XID rules, so that a bad paste is reported instead of panicking.
+fn is_ident(s: &str) -> bool {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) if first == '_' || unicode_ident::is_xid_start(first) => {
+            s != "_" && chars.all(unicode_ident::is_xid_continue)
+        }
+        _ => false,
+    }
+}
+
+impl Paste {
+    fn fragment(&self, value: &Value) -> Result<String> {
+        let fragment = || value.paste_fragment(&self.var);
+        match &self.format {
+            Format::Plain => fragment(),
+            Format::Upper => Ok(fragment()?.to_uppercase()),
+            Format::Lower => Ok(fragment()?.to_lowercase()),
+            Format::Snake => Ok(snake_case(&fragment()?)),
+            Format::Camel => Ok(camel_case(&fragment()?)),
+            Format::Int { width, radix, spec } => {
+                let value = match value {
+                    Value::Int(value) if !value.is_negative() => value.magnitude(),
+                    _ => {
+                        return Err(Error::new_spanned(
+                            spec,
+                            "padding and radix formats require a non-negative integer",
+                        ))
+                    }
+                };
+                let width = *width;
+                Ok(match radix {
+                    None => format!("{:0width$}", value),
+                    Some('x') => format!("{:0width$x}", value),
+                    Some('X') => format!("{:0width$X}", value),
+                    Some('b') => format!("{:0width$b}", value),
+                    Some(_) => format!("{:0width$o}", value),
+                })
+            }
+        }
+    }
+}
+
+impl Format {
+    fn parse(spec: TokenStream2) -> Result<Self> {
+        let format: String = spec.clone().into_iter().map(|tt| tt.to_string()).collect();
+        match format.as_str() {
+            "upper" => return Ok(Self::Upper),
+            "lower" => return Ok(Self::Lower),
+            "snake" => return Ok(Self::Snake),
+            "camel" => return Ok(Self::Camel),
+            _ => {}
+        }
+        let (width, radix) = match format.char_indices().last() {
+            Some((i, radix @ ('x' | 'X' | 'b' | 'o'))) => (&format[..i], Some(radix)),
+            _ => (format.as_str(), None),
+        };
+        // Padding must be with zeros since a space is not allowed in an
+        // identifier.
+        let width = match width {
+            "" => Some(0),
+            zero_padded if zero_padded.starts_with('0') => zero_padded.parse().ok(),
+            _ => None,
+        };
+        // Wider padding would only add leading zeros, and asking `format!` for
+        // an absurd width can exhaust memory.
+        let max_width = match radix {
+            None => 39,
+            Some('x' | 'X') => 32,
+            Some('b') => 128,
+            Some(_) => 43,
+        };
+        match width {
+            Some(width) if width > max_width => Err(Error::new_spanned(
+                spec,
+                format!(
+                    "padding width {} is wider than any integer in this radix, which has at \
+                     most {} digits",
+                    width, max_width
+                ),
+            )),
+            Some(width) => Ok(Self::Int { width, radix, spec }),
+            None => Err(Error::new_spanned(
+                spec,
+                format!(
+                    "unsupported paste format `{}`; expected zero padding and radix such as \
+                     `02`, `X`, `04b`, or one of `upper`, `lower`, `snake`, `camel`",
+                    format
+                ),
+            )),
+        }
+    }
+}
+
+fn words(s: &str) -> Vec<String> {
+    let mut words = vec![];
+    let mut current = String::new();
+    let mut prev_lower = false;
+    for ch in s.chars() {
+        if ch == '_' || ch == '-' || ch.is_whitespace() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if ch.is_uppercase() && prev_lower {
+            words.push(std::mem::take(&mut current));
+        }
+        prev_lower = ch.is_lowercase() || ch.is_ascii_digit();
+        current.push(ch);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn snake_case(s: &str) -> String {
+    words(s)
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+fn camel_case(s: &str) -> String {
+    words(s)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().into_iter().flat_map(char::to_uppercase);
+            first
+                .chain(chars.flat_map(char::to_lowercase))
+                .collect::<String>()
+        })
+        .collect()
+}
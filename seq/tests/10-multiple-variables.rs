@@ -0,0 +1,33 @@
+// Several loop variables separated by commas iterate over the cartesian
+// product of their ranges, and each of them can be pasted into the same
+// identifier.
+//
+// A repeat section may name the variable it binds, as in `#R(...)*`, so that
+// sections nested inside it can bind a different variable than the outer one.
+// An unnamed `#(...)*` binds every variable not already bound by an enclosing
+// section.
+
+use seq::seq;
+
+seq!(R in 0..2, C in 0..3 {
+    fn cell_~R~C() -> u64 {
+        R * 10 + C
+    }
+});
+
+seq!(R in 0..2, C in 0..3 {
+    const GRID: [[u64; 3]; 2] = [
+        #R(
+            [#C(R * 10 + C,)*],
+        )*
+    ];
+
+    const FLAT: [u64; 6] = [#(R * 10 + C,)*];
+});
+
+fn main() {
+    assert_eq!(cell_00(), 0);
+    assert_eq!(cell_12(), 12);
+    assert_eq!(GRID, [[0, 1, 2], [10, 11, 12]]);
+    assert_eq!(FLAT, [0, 1, 2, 10, 11, 12]);
+}
@@ -0,0 +1,33 @@
+// Ranges may be stepped and reversed with `.step_by(n)` and `.rev()`, and
+// their bounds may be written as arithmetic on integer literals. The macro
+// evaluates these itself since a proc macro cannot see `const` items.
+
+use seq::seq;
+
+seq!(N in (0..64).step_by(8) {
+    const STEPPED: [u64; 8] = [#(N,)*];
+});
+
+seq!(N in (0..4).rev() {
+    const REVERSED: [u64; 4] = [#(N,)*];
+});
+
+seq!(N in 0..2*8 {
+    const PRODUCT: [u64; 16] = [#(N,)*];
+});
+
+seq!(N in 1..(1 << 4) {
+    const SHIFTED: [u64; 15] = [#(N,)*];
+});
+
+seq!(N in ((0..=10).step_by(5)).rev() {
+    const COMBINED: [u64; 3] = [#(N,)*];
+});
+
+fn main() {
+    assert_eq!(STEPPED, [0, 8, 16, 24, 32, 40, 48, 56]);
+    assert_eq!(REVERSED, [3, 2, 1, 0]);
+    assert_eq!(PRODUCT[15], 15);
+    assert_eq!(SHIFTED[14], 15);
+    assert_eq!(COMBINED, [10, 5, 0]);
+}
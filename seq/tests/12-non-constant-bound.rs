@@ -0,0 +1,12 @@
+// Bounds that are not built from literals cannot be evaluated by the macro.
+// The error should point at the expression that could not be evaluated.
+
+use seq::seq;
+
+const LEN: u64 = 4;
+
+seq!(N in 0..LEN * 2 {
+    fn f~N() {}
+});
+
+fn main() {}
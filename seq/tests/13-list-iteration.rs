@@ -0,0 +1,59 @@
+// Besides integer ranges, a loop variable may iterate over an explicit list
+// of types, identifiers or literals. String and char literals contribute
+// their contents when pasted into an identifier.
+//
+// `(i, T) in enumerate [...]` additionally binds `i` to the position of each
+// element.
+
+use seq::seq;
+
+trait Width {
+    const BITS: u32;
+}
+
+seq!(T in [u8, u16, u32, u64] {
+    impl Width for T {
+        const BITS: u32 = T::BITS;
+    }
+});
+
+seq!(T in [Vec<u8>, Option<&'static str>] {
+    #(
+        const _: Option<T> = None;
+    )*
+});
+
+seq!(S in ["alpha", "beta"] {
+    const NAMES: [&str; 2] = [#(S,)*];
+
+    #(
+        fn name_~S() -> &'static str {
+            S
+        }
+    )*
+});
+
+seq!(C in ['x', 'y', 'z'] {
+    #(
+        fn axis_~C() -> char {
+            C
+        }
+    )*
+});
+
+seq!((i, T) in enumerate [u8, u16, u32] {
+    #(
+        const SIZE_~i: usize = std::mem::size_of::<T>();
+    )*
+
+    const INDICES: [usize; 3] = [#(i,)*];
+});
+
+fn main() {
+    assert_eq!(<u16 as Width>::BITS, 16);
+    assert_eq!(NAMES, ["alpha", "beta"]);
+    assert_eq!(name_beta(), "beta");
+    assert_eq!(axis_y(), 'y');
+    assert_eq!(SIZE_2, 4);
+    assert_eq!(INDICES, [0, 1, 2]);
+}
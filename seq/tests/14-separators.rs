@@ -0,0 +1,38 @@
+// A single punctuation token between the closing paren of a repeat section
+// and its `*` is emitted between repetitions, following the conventions of
+// `quote!` and `macro_rules!`. No separator is emitted before the first or
+// after the last repetition.
+
+use seq::seq;
+
+seq!(N in 0..4 {
+    fn sum(#(a~N: u64),*) -> u64 {
+        0 #(+ a~N)*
+    }
+
+    type Quad = (#(u64),*);
+});
+
+seq!(N in 0..3 {
+    fn classify(n: u64) -> &'static str {
+        match n {
+            #(N)|* => "small",
+            _ => "large",
+        }
+    }
+
+    fn statements() -> u64 {
+        let mut total = 0;
+        #(total += N);*;
+        total
+    }
+});
+
+fn main() {
+    assert_eq!(sum(1, 2, 3, 4), 10);
+    let quad: Quad = (1, 2, 3, 4);
+    assert_eq!(quad.3, 4);
+    assert_eq!(classify(2), "small");
+    assert_eq!(classify(3), "large");
+    assert_eq!(statements(), 3);
+}
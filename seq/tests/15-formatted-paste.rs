@@ -0,0 +1,46 @@
+// `prefix~{N:spec}` pastes a formatted value. Integers accept zero padding and
+// a radix (`x`, `X`, `b` or `o`), and any pasted piece can have its case
+// converted with `upper`, `lower`, `snake` or `camel`. The resulting
+// identifier keeps the span of the prefix.
+
+#![allow(non_upper_case_globals, non_snake_case)]
+
+use seq::seq;
+
+seq!(N in 8..12 {
+    #(
+        const REG_~{N:02X}: u64 = N;
+        const irq_~{N:03}: u64 = N;
+        const mask_~{N:b}: u64 = N;
+        const oct_~{N:o}: u64 = N;
+        const hex_~{N:04x}: u64 = N;
+    )*
+});
+
+seq!(T in [u8, u16] {
+    #(
+        struct Wrap~{T:upper};
+        struct Word~{T:camel};
+    )*
+});
+
+seq!(S in ["HttpServer", "io_error"] {
+    #(
+        fn handle_~{S:snake}() -> &'static str {
+            S
+        }
+        struct Kind~{S:camel};
+    )*
+});
+
+fn main() {
+    assert_eq!(REG_0A, 10);
+    assert_eq!(irq_009, 9);
+    assert_eq!(mask_1011, 11);
+    assert_eq!(oct_10, 8);
+    assert_eq!(hex_000b, 11);
+    let _ = (WrapU8, WrapU16, WordU8, WordU16);
+    assert_eq!(handle_http_server(), "HttpServer");
+    assert_eq!(handle_io_error(), "io_error");
+    let _ = (KindHttpServer, KindIoError);
+}
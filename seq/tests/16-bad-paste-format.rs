@@ -0,0 +1,19 @@
+// Unknown paste formats are rejected at the format itself. Padding with
+// anything other than zeros would not produce a valid identifier, and padding
+// wider than the largest integer in the radix is refused.
+
+use seq::seq;
+
+seq!(N in 0..4 {
+    #(
+        fn reg_~{N:4}() {}
+    )*
+});
+
+seq!(N in 0..4 {
+    #(
+        fn wide_~{N:0999999999999}() {}
+    )*
+});
+
+fn main() {}
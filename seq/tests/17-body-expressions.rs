@@ -0,0 +1,45 @@
+// Inside the body, `#{expr}` expands to the integer literal that `expr`
+// evaluates to, where `expr` is arithmetic on literals and the loop variables
+// bound at that point.
+//
+// `#if cond { ... }` includes its tokens only for the iterations where `cond`
+// holds, with an optional `else { ... }` for the others. An iteration that
+// expands to nothing gets no separator either.
+
+use seq::seq;
+
+seq!(N in 0..4 {
+    const OFFSETS: [u64; 4] = [#(#{N * 4},)*];
+    const NEXT: [u64; 4] = [#(#{(N + 1) % 4},)*];
+
+    #(
+        #if N % 2 == 0 {
+            const BANK_~N: &str = "even";
+        } else {
+            const BANK_~N: &str = "odd";
+        }
+    )*
+
+    const EVENS: [u64; 2] = [#(#if N % 2 == 0 { N, })*];
+    const MIDDLE: [u64; 2] = [#(#if N > 0 && !(N == 3) { N, })*];
+    const EVENS_SEPARATED: [u64; 2] = [#(#if N % 2 == 0 { N }),*];
+    const ODDS_SEPARATED: [u64; 2] = [#(#if N % 2 == 1 { N }),*];
+});
+
+seq!(N in 1..3 {
+    fn reg_~N() -> u64 {
+        #{1 << N}
+    }
+});
+
+fn main() {
+    assert_eq!(OFFSETS, [0, 4, 8, 12]);
+    assert_eq!(NEXT, [1, 2, 3, 0]);
+    assert_eq!(BANK_0, "even");
+    assert_eq!(BANK_3, "odd");
+    assert_eq!(EVENS, [0, 2]);
+    assert_eq!(MIDDLE, [1, 2]);
+    assert_eq!(EVENS_SEPARATED, [0, 2]);
+    assert_eq!(ODDS_SEPARATED, [1, 3]);
+    assert_eq!(reg_2(), 4);
+}
@@ -0,0 +1,64 @@
+// Substituted integers keep the type suffix of the range bounds. Ranges may
+// be negative, span the full `i128` and `u128` types, or iterate over chars.
+// A negative value behaves as a single operand, so methods called on it apply
+// to the whole value.
+
+use seq::seq;
+
+seq!(N in 0u8..4u8 {
+    const BYTES: [u8; 4] = [#(N,)*];
+    const WIDENED: [u16; 4] = [#(N as u16,)*];
+});
+
+seq!(N in 0..=2u64 {
+    const SUFFIXED: [u64; 3] = [#(N,)*];
+});
+
+seq!(N in -4..4 {
+    const SIGNED: [i32; 8] = [#(N,)*];
+    const NEXT: [i32; 8] = [#(#{N + 1},)*];
+});
+
+seq!(N in -2i32..2i32 {
+    const ABS: [i32; 4] = [#(N.abs(),)*];
+    const SQUARES: [i32; 4] = [#(N.pow(2),)*];
+    const SHIFTED: [i32; 4] = [#(#{N - 1}.abs(),)*];
+});
+
+seq!(N in (-8i8..=-6i8).rev() {
+    const NEGATIVE: [i8; 3] = [#(N,)*];
+});
+
+seq!(N in 340282366920938463463374607431768211453u128..=340282366920938463463374607431768211455u128 {
+    const TOP: [u128; 3] = [#(N,)*];
+});
+
+seq!(N in -170141183460469231731687303715884105728i128..-170141183460469231731687303715884105726i128 {
+    const BOTTOM: [i128; 2] = [#(N,)*];
+});
+
+seq!(C in 'a'..='e' {
+    const LETTERS: [char; 5] = [#(C,)*];
+
+    #(
+        fn letter_~C() -> char {
+            C
+        }
+    )*
+});
+
+fn main() {
+    assert_eq!(BYTES, [0, 1, 2, 3]);
+    assert_eq!(WIDENED, [0, 1, 2, 3]);
+    assert_eq!(SUFFIXED, [0, 1, 2]);
+    assert_eq!(SIGNED, [-4, -3, -2, -1, 0, 1, 2, 3]);
+    assert_eq!(NEXT, [-3, -2, -1, 0, 1, 2, 3, 4]);
+    assert_eq!(ABS, [2, 1, 0, 1]);
+    assert_eq!(SQUARES, [4, 1, 0, 1]);
+    assert_eq!(SHIFTED, [3, 2, 1, 0]);
+    assert_eq!(NEGATIVE, [-6, -7, -8]);
+    assert_eq!(TOP, [u128::MAX - 2, u128::MAX - 1, u128::MAX]);
+    assert_eq!(BOTTOM, [i128::MIN, i128::MIN + 1]);
+    assert_eq!(LETTERS, ['a', 'b', 'c', 'd', 'e']);
+    assert_eq!(letter_d(), 'd');
+}
@@ -0,0 +1,14 @@
+// Bounds with different integer types, values that do not fit the suffixed
+// type, and ranges mixing chars with integers are rejected at the bound.
+
+use seq::seq;
+
+seq!(N in 0u8..4u16 {});
+
+seq!(N in 0u8..=256u8 {});
+
+seq!(N in -1..4u32 {});
+
+seq!(C in 'a'..10 {});
+
+fn main() {}
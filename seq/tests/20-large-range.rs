@@ -0,0 +1,14 @@
+// Lookup tables with tens of thousands of entries should expand quickly. The
+// body is analysed once and only the substitution sites are revisited for
+// each iteration.
+
+use seq::seq;
+
+seq!(N in 0..65536 {
+    static SQUARES_MOD: [u16; 65536] = [#(#{N * N % 65536},)*];
+});
+
+fn main() {
+    assert_eq!(SQUARES_MOD[3], 9);
+    assert_eq!(SQUARES_MOD[65535], 1);
+}
@@ -0,0 +1,27 @@
+// Misuse of `~` is reported where it happens: pasting a variable that is not
+// declared, pasting a variable outside of the section that binds it, a `~`
+// with no identifier before it to paste into, and a paste whose result is not
+// an identifier.
+
+use seq::seq;
+
+seq!(N in 0..4 {
+    fn f~M() {}
+});
+
+seq!(N in 0..4 {
+    fn g~N() {}
+    #(
+        fn h~N() {}
+    )*
+});
+
+seq!(N in 0..4 {
+    const VALUE: u64 = ~N;
+});
+
+seq!(S in ["a\u{b2}"] {
+    fn f~S() {}
+});
+
+fn main() {}
@@ -0,0 +1,15 @@
+// A range that yields nothing is almost always a typo in a bound, so empty
+// ranges, reversed bounds and empty lists are rejected instead of expanding to
+// nothing.
+
+use seq::seq;
+
+seq!(N in 4..4 {});
+
+seq!(N in 8..0 {});
+
+seq!(C in 'z'..='a' {});
+
+seq!(T in [] {});
+
+fn main() {}
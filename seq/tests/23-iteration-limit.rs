@@ -0,0 +1,13 @@
+// An accidental huge range is reported instead of keeping rustc busy. The
+// default limit is 100000 repetitions of the body, counting every combination
+// of loops repeated together.
+
+use seq::seq;
+
+seq!(N in 0..10_000_000 {});
+
+seq!(R in 0..1000, C in 0..1000 {});
+
+seq!(#![max_iterations = 8] N in 0..16 {});
+
+fn main() {}
@@ -0,0 +1,18 @@
+// `#![max_iterations = N]` adjusts the limit for a single invocation. Stepping
+// through a range counts the values actually visited, so a sparse walk over a
+// wide range stays under the limit.
+
+use seq::seq;
+
+seq!(#![max_iterations = 4] N in 0..4 {
+    const FOUR: usize = 0 #(+ 1 + N * 0)*;
+});
+
+seq!(N in (0u64..1_000_000_000_000).step_by(250_000_000_000) {
+    const STEPS: [u64; 4] = [#(N,)*];
+});
+
+fn main() {
+    assert_eq!(FOUR, 4);
+    assert_eq!(STEPS, [0, 250_000_000_000, 500_000_000_000, 750_000_000_000]);
+}
@@ -0,0 +1,19 @@
+// Errors in `#{expr}` point into the braces: a missing or incomplete
+// expression, and a result that does not fit the type of the loop variable,
+// such as negating an unsigned value.
+
+use seq::seq;
+
+seq!(N in 0u8..4u8 {
+    const NEGATED: [u8; 4] = [#(#{-N},)*];
+});
+
+seq!(N in 0..4 {
+    const EMPTY: u64 = #{};
+});
+
+seq!(N in 0..4 {
+    const INCOMPLETE: u64 = #{N +};
+});
+
+fn main() {}
@@ -0,0 +1,28 @@
+// A seq! invocation inside the body of another expands on its own. The outer
+// macro only substitutes its own variables there, leaving pastes of the inner
+// variables and the inner `#(...)*` sections for the inner macro.
+
+use seq::seq;
+
+seq!(N in 0..3 {
+    #(
+        seq!(M in 0..2 {
+            fn f~N~M() -> u64 {
+                N * 10 + M
+            }
+        });
+    )*
+});
+
+seq!(N in 1..3 {
+    seq!(M in 0..N {
+        const ROW_~N: [u64; N] = [#(#{M * 2},)*];
+    });
+});
+
+fn main() {
+    assert_eq!(f00(), 0);
+    assert_eq!(f21(), 21);
+    assert_eq!(ROW_1, [0]);
+    assert_eq!(ROW_2, [0, 2]);
+}
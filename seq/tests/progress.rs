@@ -1,13 +1,30 @@
 #[test]
 fn tests() {
     let t = trybuild::TestCases::new();
-    //t.pass("tests/01-parse-header.rs");
-    //t.pass("tests/02-parse-body.rs");
-    //t.compile_fail("tests/03-expand-four-errors.rs");
-    //t.pass("tests/04-paste-ident.rs");
-    //t.pass("tests/05-repeat-section.rs");
-    //t.pass("tests/06-init-array.rs");
-    //t.pass("tests/07-inclusive-range.rs");
-    //t.compile_fail("tests/08-ident-span.rs");
-    //t.pass("tests/09-interaction-with-macrorules.rs");
+    t.pass("tests/01-parse-header.rs");
+    t.pass("tests/02-parse-body.rs");
+    t.compile_fail("tests/03-expand-four-errors.rs");
+    t.pass("tests/04-paste-ident.rs");
+    t.pass("tests/05-repeat-section.rs");
+    t.pass("tests/06-init-array.rs");
+    t.pass("tests/07-inclusive-range.rs");
+    t.compile_fail("tests/08-ident-span.rs");
+    t.pass("tests/09-interaction-with-macrorules.rs");
+    t.pass("tests/10-multiple-variables.rs");
+    t.pass("tests/11-range-adapters.rs");
+    t.compile_fail("tests/12-non-constant-bound.rs");
+    t.pass("tests/13-list-iteration.rs");
+    t.pass("tests/14-separators.rs");
+    t.pass("tests/15-formatted-paste.rs");
+    t.compile_fail("tests/16-bad-paste-format.rs");
+    t.pass("tests/17-body-expressions.rs");
+    t.pass("tests/18-typed-ranges.rs");
+    t.compile_fail("tests/19-bad-range-types.rs");
+    t.pass("tests/20-large-range.rs");
+    t.compile_fail("tests/21-paste-errors.rs");
+    t.compile_fail("tests/22-empty-ranges.rs");
+    t.compile_fail("tests/23-iteration-limit.rs");
+    t.pass("tests/24-raised-limit.rs");
+    t.compile_fail("tests/25-bad-body-expressions.rs");
+    t.pass("tests/26-nested-seq.rs");
 }
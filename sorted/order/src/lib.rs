@@ -0,0 +1,227 @@
+// Ordering checks behind #[sorted], shared by enums and match expressions. A
+// proc-macro crate cannot export ordinary functions, so they live here, where
+// tooling that wants the same fixes, such as an editor action or a bulk-fix
+// command, can depend on them too.
+
+// Moving the item at `item` to just before the item at `before`, or to the end
+// for `None`. Both are indices into the original order.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Move {
+    pub item: usize,
+    pub before: Option<usize>,
+}
+
+// How names are compared. By default names compare as plain strings, so
+// `Http10` sorts before `Http2` and `Zebra` before `apple`.
+#[derive(Clone, Copy, Default)]
+pub struct Collation {
+    // Runs of digits compare by their numeric value.
+    pub natural: bool,
+    pub case_insensitive: bool,
+}
+
+// A name prepared for comparison under some `Collation`. Names that only
+// differ in what the collation ignores, such as case, compare equal and keep
+// their written order.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+pub struct Key {
+    chunks: Vec<Chunk>,
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum Chunk {
+    // Digits without leading zeros, compared by length first so that numbers
+    // of any size compare by value. Like digits in plain order, numbers sort
+    // before text.
+    Number(usize, String),
+    Text(String),
+}
+
+impl Collation {
+    pub fn key(self, name: &str) -> Key {
+        let text = if self.case_insensitive {
+            name.to_lowercase()
+        } else {
+            name.to_owned()
+        };
+        let chunks = if self.natural {
+            let mut chunks = vec![];
+            let mut rest = text.as_str();
+            while let Some(first) = rest.chars().next() {
+                let digits = first.is_ascii_digit();
+                let len = rest
+                    .find(|ch: char| ch.is_ascii_digit() != digits)
+                    .unwrap_or(rest.len());
+                let (run, tail) = rest.split_at(len);
+                chunks.push(if digits {
+                    let value = run.trim_start_matches('0');
+                    Chunk::Number(value.len(), value.to_owned())
+                } else {
+                    Chunk::Text(run.to_owned())
+                });
+                rest = tail;
+            }
+            chunks
+        } else {
+            vec![Chunk::Text(text)]
+        };
+        Key { chunks }
+    }
+}
+
+// For every item that sorts before some earlier item, its index and the index
+// of the first earlier item it should come before.
+pub fn violations<T: Ord>(keys: &[T]) -> Vec<(usize, usize)> {
+    let mut violations = vec![];
+    for (i, key) in keys.iter().enumerate() {
+        if let Some(before) = keys[..i].iter().position(|seen| seen > key) {
+            violations.push((i, before));
+        }
+    }
+    violations
+}
+
+// Indices of `keys` in sorted order. Equal keys keep their relative order.
+pub fn sorted_order<T: Ord>(keys: &[T]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..keys.len()).collect();
+    order.sort_by(|&a, &b| keys[a].cmp(&keys[b]));
+    order
+}
+
+// The fewest moves that sort `keys`. Items on a longest run that is already in
+// order stay put and every other item moves in front of its successor in
+// sorted order. Applying the moves one after another in the returned order
+// yields the sorted sequence.
+pub fn minimal_moves<T: Ord>(keys: &[T]) -> Vec<Move> {
+    let order = sorted_order(keys);
+    let mut ranks = vec![0; keys.len()];
+    for (rank, &i) in order.iter().enumerate() {
+        ranks[i] = rank;
+    }
+    let kept = longest_increasing(&ranks);
+    (0..order.len())
+        .rev()
+        .filter(|&rank| !kept[order[rank]])
+        .map(|rank| Move {
+            item: order[rank],
+            before: order.get(rank + 1).copied(),
+        })
+        .collect()
+}
+
+// Marks the items on one longest strictly increasing run of `ranks`.
+fn longest_increasing(ranks: &[usize]) -> Vec<bool> {
+    // `tails[k]` is the item ending the lowest increasing run of length `k + 1`
+    // found so far.
+    let mut tails: Vec<usize> = vec![];
+    let mut prev = vec![None; ranks.len()];
+    for (i, &rank) in ranks.iter().enumerate() {
+        let k = tails.partition_point(|&tail| ranks[tail] < rank);
+        if k > 0 {
+            prev[i] = Some(tails[k - 1]);
+        }
+        if k == tails.len() {
+            tails.push(i);
+        } else {
+            tails[k] = i;
+        }
+    }
+    let mut kept = vec![false; ranks.len()];
+    let mut next = tails.last().copied();
+    while let Some(i) = next {
+        kept[i] = true;
+        next = prev[i];
+    }
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply<T: Clone>(keys: &[T], moves: &[Move]) -> Vec<T> {
+        let mut items: Vec<usize> = (0..keys.len()).collect();
+        for m in moves {
+            items.retain(|&i| i != m.item);
+            let at = match m.before {
+                Some(before) => items.iter().position(|&i| i == before).unwrap(),
+                None => items.len(),
+            };
+            items.insert(at, m.item);
+        }
+        items.into_iter().map(|i| keys[i].clone()).collect()
+    }
+
+    #[test]
+    fn sorted_input_needs_no_moves() {
+        assert_eq!(minimal_moves(&["A", "B", "C"]), vec![]);
+    }
+
+    #[test]
+    fn single_misplaced_item_moves_once() {
+        let keys = ["B", "C", "D", "E", "A"];
+        let moves = minimal_moves(&keys);
+        assert_eq!(
+            moves,
+            vec![Move {
+                item: 4,
+                before: Some(0)
+            }]
+        );
+        assert_eq!(apply(&keys, &moves), ["A", "B", "C", "D", "E"]);
+    }
+
+    #[test]
+    fn moves_sort_everything() {
+        let keys = ["Io", "Fmt", "Utf8", "Env", "Parse", "Json", "Alloc", "Zip"];
+        let moves = minimal_moves(&keys);
+        let mut sorted = keys;
+        sorted.sort();
+        assert_eq!(apply(&keys, &moves), sorted);
+        // `Fmt`, `Json`, `Zip` or an equally long run stays in place.
+        assert_eq!(moves.len(), keys.len() - 3);
+    }
+
+    #[test]
+    fn reversed_input_keeps_one_item() {
+        let keys = [5, 4, 3, 2, 1];
+        let moves = minimal_moves(&keys);
+        assert_eq!(moves.len(), 4);
+        assert_eq!(apply(&keys, &moves), [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn natural_and_case_insensitive_keys() {
+        let sorted = |collation: Collation, names: &[&str]| {
+            let keys: Vec<Key> = names.iter().map(|name| collation.key(name)).collect();
+            violations(&keys).is_empty()
+        };
+        let natural = Collation {
+            natural: true,
+            case_insensitive: false,
+        };
+        let case_insensitive = Collation {
+            natural: false,
+            case_insensitive: true,
+        };
+        assert!(sorted(
+            Collation::default(),
+            &["Http10", "Http2", "Zebra", "apple"]
+        ));
+        assert!(sorted(
+            natural,
+            &["Http", "Http2", "Http10", "Http010a", "Http10b"]
+        ));
+        assert!(!sorted(natural, &["Http10", "Http2"]));
+        assert!(sorted(natural, &["1", "2", "10", "a", "b1"]));
+        assert!(!sorted(natural, &["a", "1"]));
+        assert!(sorted(case_insensitive, &["apple", "Banana", "cherry"]));
+        assert!(sorted(case_insensitive, &["Apple", "apple"]));
+        assert!(sorted(case_insensitive, &["apple", "Apple", "banana"]));
+    }
+
+    #[test]
+    fn violations_point_at_first_greater_item() {
+        assert_eq!(violations(&["C", "A", "D", "B"]), vec![(1, 0), (3, 0)]);
+    }
+}
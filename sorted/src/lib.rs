@@ -1,19 +1,25 @@
 use proc_macro::TokenStream;
-use proc_macro2::{Ident, Span};
-use quote::quote;
+use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use sorted_order::Collation;
 use syn::{
-    parse::Parse, parse_macro_input, spanned::Spanned, visit_mut::VisitMut, Error, Item, ItemEnum,
-    ItemFn, Pat, Path,
+    meta::ParseNestedMeta, parse::Parse, parse_macro_input, spanned::Spanned, visit_mut,
+    visit_mut::VisitMut, Error, Expr, ExprLit, ExprRange, Fields, ImplItem, Item, ItemConst,
+    ItemEnum, ItemMod, ItemStatic, ItemStruct, Lit, LitStr, Meta, Pat, PatIdent, Path, RangeLimits,
+    Stmt, TraitItem, UseTree,
 };
 
 #[proc_macro_attribute]
-pub fn sorted(_args: TokenStream, input: TokenStream) -> TokenStream {
+pub fn sorted(args: TokenStream, input: TokenStream) -> TokenStream {
     // match handle_sorted(input) {
     //     Ok(tokens) => tokens.into(),
     //     Err(e) => e.into_compile_error().into(),
     // }
+    let mut options = SortOptions::default();
+    let options_parser = syn::meta::parser(|meta| options.parse_meta(meta));
+    parse_macro_input!(args with options_parser);
     let sorted_input = parse_macro_input!(input as SortedInput);
-    sorted_input.build()
+    sorted_input.build(options)
 }
 
 #[proc_macro_attribute]
@@ -23,15 +29,28 @@ pub fn check(_args: TokenStream, input: TokenStream) -> TokenStream {
 }
 
 struct CheckInput {
-    syntax_tree: ItemFn,
+    syntax_tree: Item,
 }
 
 impl Parse for CheckInput {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let item: Item = input.parse()?;
-        let Item::Fn(syntax_tree) = item else {
-            return Err(Error::new(input.span(), "expected fn expression"));
-        };
+        let syntax_tree: Item = input.parse()?;
+        match &syntax_tree {
+            Item::Fn(_) | Item::Impl(_) | Item::Trait(_) | Item::Const(_) | Item::Static(_) => {}
+            Item::Mod(item) if item.content.is_some() => {}
+            Item::Mod(item) => {
+                return Err(Error::new_spanned(
+                    item,
+                    "expected an inline module; #[sorted::check] cannot see into other files",
+                ))
+            }
+            _ => {
+                return Err(Error::new(
+                    input.span(),
+                    "expected fn, impl, trait, mod, const or static item",
+                ))
+            }
+        }
         Ok(Self { syntax_tree })
     }
 }
@@ -55,8 +74,26 @@ impl ArmPath {
         &self.path.segments.last().unwrap().ident
     }
 
-    fn span(&self) -> Span {
-        self.path.span()
+    // The segments compared for this path, only the variant name unless
+    // comparing full paths.
+    fn key(&self, options: &SortOptions) -> Vec<sorted_order::Key> {
+        let segments = &self.path.segments;
+        let compared = if options.full_path { segments.len() } else { 1 };
+        segments
+            .iter()
+            .skip(segments.len() - compared)
+            .map(|segment| options.collation.key(&segment.ident.to_string()))
+            .collect()
+    }
+
+    // The path up to the variant, such as `Error` for `Error::Io`.
+    fn prefix(&self) -> Vec<String> {
+        let segments = &self.path.segments;
+        segments
+            .iter()
+            .take(segments.len() - 1)
+            .map(|segment| segment.ident.to_string())
+            .collect()
     }
 
     fn display_string(&self) -> String {
@@ -71,57 +108,235 @@ impl ArmPath {
     }
 }
 
-impl VisitMut for Checker {
-    fn visit_expr_match_mut(&mut self, item: &mut syn::ExprMatch) {
-        let Some(sorted_attr_index) = item.attrs.iter().enumerate().find_map(|(index, attr)| {
-            if attr.path().is_ident("sorted") {
-                return Some(index);
+// The part of a match arm's pattern that decides its place in a #[sorted]
+// match, along with how to show and point at it.
+struct ArmKey {
+    key: PatKey,
+    display: String,
+    tokens: TokenStream2,
+    // The variant matched by the arm, if any.
+    path: Option<ArmPath>,
+}
+
+// Ordering of patterns: variants by name, or by every segment of their path,
+// literals by value, ranges by their start and tuples element by element. A
+// wildcard inside a tuple sorts last.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum PatKey {
+    Int(i128),
+    // Integers above `i128::MAX`, which only a `u128` can hold.
+    LargeInt(u128),
+    Char(char),
+    Str(String),
+    Path(Vec<sorted_order::Key>),
+    Tuple(Vec<PatKey>),
+    Wild,
+}
+
+impl ArmKey {
+    fn new(pat: &Pat, options: &SortOptions) -> syn::Result<Self> {
+        let path = |path: &Path| {
+            let arm_path = ArmPath::from(path.clone());
+            Self {
+                key: PatKey::Path(arm_path.key(options)),
+                display: arm_path.display_string(),
+                tokens: path.to_token_stream(),
+                path: Some(arm_path),
             }
-            None
-        }) else {
-            return;
         };
-        item.attrs.remove(sorted_attr_index);
-        let mut seen_arm_paths: Vec<ArmPath> = vec![];
-        for (i, arm) in item.arms.iter().enumerate() {
-            let this_path = match &arm.pat {
-                Pat::Ident(ident) => ident.ident.clone().into(),
-                Pat::Path(pat) => pat.path.clone(),
-                Pat::TupleStruct(pat) => pat.path.clone(),
-                Pat::Struct(pat) => pat.path.clone(),
-                Pat::Wild(_) => {
-                    if i == item.arms.len() - 1 {
-                        return;
-                    } else {
-                        panic!("Bad underscore position");
+        let unsupported = || Error::new(pat.span(), "unsupported by #[sorted]");
+        Ok(match pat {
+            Pat::Ident(PatIdent {
+                subpat: Some((_, subpat)),
+                ..
+            }) => Self::new(subpat, options)?,
+            Pat::Ident(ident) => path(&ident.ident.clone().into()),
+            Pat::Path(pat) => path(&pat.path),
+            Pat::TupleStruct(pat) => path(&pat.path),
+            Pat::Struct(pat) => path(&pat.path),
+            Pat::Or(or) => Self::new(&or.cases[0], options)?,
+            Pat::Paren(paren) => Self::new(&paren.pat, options)?,
+            Pat::Reference(reference) => Self::new(&reference.pat, options)?,
+            Pat::Lit(lit) => Self {
+                key: lit_key(&lit.lit).ok_or_else(unsupported)?,
+                display: lit.lit.to_token_stream().to_string(),
+                tokens: pat.to_token_stream(),
+                path: None,
+            },
+            Pat::Range(ExprRange {
+                start: Some(start),
+                limits,
+                end,
+                ..
+            }) => {
+                let start = match &**start {
+                    Expr::Lit(lit) => {
+                        lit_key(&lit.lit).map(|key| (key, lit.to_token_stream().to_string()))
+                    }
+                    Expr::Path(start) => {
+                        let start = path(&start.path);
+                        Some((start.key, start.display))
                     }
+                    _ => None,
+                };
+                let (key, start) = start.ok_or_else(unsupported)?;
+                let limits = match limits {
+                    RangeLimits::HalfOpen(_) => "..",
+                    RangeLimits::Closed(_) => "..=",
+                };
+                let end = end.as_ref().map(|end| end.to_token_stream().to_string());
+                Self {
+                    key,
+                    display: format!("{}{}{}", start, limits, end.unwrap_or_default()),
+                    tokens: pat.to_token_stream(),
+                    path: None,
                 }
+            }
+            Pat::Tuple(tuple) => {
+                let mut keys = vec![];
+                let mut displays = vec![];
+                for elem in &tuple.elems {
+                    let (key, display) = match elem {
+                        Pat::Wild(_) | Pat::Rest(_) => {
+                            (PatKey::Wild, elem.to_token_stream().to_string())
+                        }
+                        elem => {
+                            let elem = Self::new(elem, options)?;
+                            (elem.key, elem.display)
+                        }
+                    };
+                    keys.push(key);
+                    displays.push(display);
+                }
+                Self {
+                    key: PatKey::Tuple(keys),
+                    display: format!("({})", displays.join(", ")),
+                    tokens: pat.to_token_stream(),
+                    path: None,
+                }
+            }
+            _ => return Err(unsupported()),
+        })
+    }
+}
+
+fn lit_key(lit: &Lit) -> Option<PatKey> {
+    match lit {
+        Lit::Int(lit) => match lit.base10_parse() {
+            Ok(value) => Some(PatKey::Int(value)),
+            Err(_) => lit.base10_parse().ok().map(PatKey::LargeInt),
+        },
+        Lit::Byte(lit) => Some(PatKey::Int(lit.value().into())),
+        Lit::Bool(lit) => Some(PatKey::Int(lit.value.into())),
+        Lit::Char(lit) => Some(PatKey::Char(lit.value())),
+        Lit::Str(lit) => Some(PatKey::Str(lit.value())),
+        _ => None,
+    }
+}
+
+// Arguments of `#[sorted(...)]` on an enum or a match expression.
+#[derive(Default)]
+struct SortOptions {
+    collation: Collation,
+    // `as_declared = "path::Enum"` on a match: arms follow the declaration
+    // order of that enum instead of being alphabetical.
+    as_declared: Option<Path>,
+    // Arms compare by their whole path rather than only the variant name.
+    full_path: bool,
+    // Arms are grouped by the path before the variant name, each group sorted
+    // on its own and the groups sorted by that path.
+    grouped: bool,
+    // Which arms at the end of a match may catch everything left over.
+    catch_all: CatchAll,
+    // `metadata` on an enum: also generate `VARIANT_NAMES`, `from_name` and
+    // `name`.
+    metadata: Option<Span>,
+    // `publish_order` on an enum: publish the declaration order for
+    // `as_declared` matches instead of checking it.
+    publish_order: bool,
+    // Options given that make no sense outside of a match, to be rejected on
+    // items.
+    match_only: Vec<(Span, &'static str)>,
+    // Options given that only make sense on an enum.
+    enum_only: Vec<(Span, &'static str)>,
+    // Where `natural` and `case_insensitive` were given, for the items on which
+    // only plain string order makes sense.
+    collation_options: Vec<(Span, &'static str)>,
+}
+
+impl SortOptions {
+    fn parse_meta(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("natural") {
+            self.collation.natural = true;
+            self.collation_options.push((meta.path.span(), "natural"));
+        } else if meta.path.is_ident("case_insensitive") {
+            self.collation.case_insensitive = true;
+            self.collation_options
+                .push((meta.path.span(), "case_insensitive"));
+        } else if meta.path.is_ident("as_declared") {
+            let enum_path: LitStr = meta.value()?.parse()?;
+            self.as_declared = Some(enum_path.parse()?);
+            self.match_only.push((enum_path.span(), "as_declared"));
+        } else if meta.path.is_ident("full_path") {
+            self.full_path = true;
+            self.match_only.push((meta.path.span(), "full_path"));
+        } else if meta.path.is_ident("grouped") {
+            self.grouped = true;
+            self.match_only.push((meta.path.span(), "grouped"));
+        } else if meta.path.is_ident("metadata") {
+            self.metadata = Some(meta.path.span());
+            self.enum_only.push((meta.path.span(), "metadata"));
+        } else if meta.path.is_ident("publish_order") {
+            self.publish_order = true;
+            self.enum_only.push((meta.path.span(), "publish_order"));
+        } else if meta.path.is_ident("catch_all") {
+            let policy: LitStr = meta.value()?.parse()?;
+            self.catch_all = match policy.value().as_str() {
+                "wildcard" => CatchAll::Wildcard,
+                "binding" => CatchAll::Binding,
                 _ => {
-                    let error = Error::new(arm.pat.span(), "unsupported by #[sorted]");
-                    self.error = Some(error);
-                    return;
+                    return Err(Error::new_spanned(
+                        policy,
+                        "expected `catch_all = \"wildcard\"` or `catch_all = \"binding\"`",
+                    ))
                 }
             };
-            let this_path: ArmPath = this_path.into();
-            if let Some(last_path) = seen_arm_paths.last() {
-                if this_path.get_ident() < last_path.get_ident() {
-                    let before = seen_arm_paths
-                        .iter()
-                        .find(|seen| seen.get_ident() > this_path.get_ident())
-                        .unwrap();
-                    let error = Error::new(
-                        this_path.span(),
-                        &format!(
-                            "{} should sort before {}",
-                            this_path.display_string(),
-                            before.display_string()
-                        ),
-                    );
-                    self.error = Some(error);
-                    return;
-                }
+            self.match_only.push((policy.span(), "catch_all"));
+        } else {
+            return Err(meta.error(
+                "unsupported #[sorted] option; expected `natural`, `case_insensitive`, \
+                 `full_path`, `grouped`, `metadata`, `publish_order`, \
+                 `as_declared = \"path::Enum\"` or `catch_all = \"...\"`",
+            ));
+        }
+        Ok(())
+    }
+}
+
+// Arms allowed after the sorted ones, with or without a guard.
+#[derive(Default, Clone, Copy)]
+enum CatchAll {
+    // Only `_`.
+    #[default]
+    Wildcard,
+    // `_` or a binding such as `other`. Identifiers starting with an uppercase
+    // letter are taken to be unit variants or constants and stay sorted.
+    Binding,
+}
+
+impl CatchAll {
+    fn matches(self, pat: &Pat) -> bool {
+        match pat {
+            Pat::Wild(_) => true,
+            Pat::Ident(PatIdent {
+                ident,
+                subpat: None,
+                ..
+            }) => {
+                matches!(self, CatchAll::Binding)
+                    && !ident.to_string().starts_with(char::is_uppercase)
             }
-            seen_arm_paths.push(this_path);
+            _ => false,
         }
     }
 }
@@ -129,73 +344,775 @@ impl VisitMut for Checker {
 #[derive(Default)]
 struct Checker {
     error: Option<Error>,
+    // Assertions for `#[sorted(as_declared = ...)]` matches, which can only be
+    // evaluated by the compiler once the enum's published order is known.
+    declared_order_checks: Vec<TokenStream2>,
+}
+
+impl VisitMut for Checker {
+    fn visit_expr_match_mut(&mut self, item: &mut syn::ExprMatch) {
+        if let Some(sorted_attr_index) = item.attrs.iter().enumerate().find_map(|(index, attr)| {
+            if attr.path().is_ident("sorted") {
+                return Some(index);
+            }
+            None
+        }) {
+            let attr = item.attrs.remove(sorted_attr_index);
+            let mut options = SortOptions::default();
+            let parsed = match attr.meta {
+                Meta::List(_) => attr.parse_nested_meta(|meta| options.parse_meta(meta)),
+                _ => Ok(()),
+            };
+            match parsed {
+                Ok(()) => self.check_arms(item, &options),
+                Err(error) => self.push_error(error),
+            }
+        }
+        // Matches nested in the arms are checked on their own.
+        visit_mut::visit_expr_match_mut(self, item);
+    }
+
+    // The `as_declared` assertions of a function go at the start of its body,
+    // where a `const _` item is allowed even when the function is an impl or
+    // trait method.
+    fn visit_item_fn_mut(&mut self, item: &mut syn::ItemFn) {
+        let checks = self.collect_checks(|checker| visit_mut::visit_item_fn_mut(checker, item));
+        prepend_checks(&mut item.block, checks);
+    }
+
+    fn visit_impl_item_fn_mut(&mut self, item: &mut syn::ImplItemFn) {
+        let checks =
+            self.collect_checks(|checker| visit_mut::visit_impl_item_fn_mut(checker, item));
+        prepend_checks(&mut item.block, checks);
+    }
+
+    fn visit_trait_item_fn_mut(&mut self, item: &mut syn::TraitItemFn) {
+        let checks =
+            self.collect_checks(|checker| visit_mut::visit_trait_item_fn_mut(checker, item));
+        if let Some(block) = &mut item.default {
+            prepend_checks(block, checks);
+        }
+    }
+}
+
+impl Checker {
+    // The assertions added while `visit` runs, leaving the ones from before in
+    // place.
+    fn collect_checks(&mut self, visit: impl FnOnce(&mut Self)) -> Vec<TokenStream2> {
+        let outer = std::mem::take(&mut self.declared_order_checks);
+        visit(self);
+        std::mem::replace(&mut self.declared_order_checks, outer)
+    }
+
+    fn check_arms(&mut self, item: &syn::ExprMatch, options: &SortOptions) {
+        for (span, option) in &options.enum_only {
+            self.push_error(Error::new(
+                *span,
+                format!("`{}` only applies to enums", option),
+            ));
+        }
+        // The catch-all arms at the end are left out of the order.
+        let sorted_len = item
+            .arms
+            .iter()
+            .rposition(|arm| !options.catch_all.matches(&arm.pat))
+            .map_or(0, |last| last + 1);
+        let mut arm_keys: Vec<ArmKey> = vec![];
+        for arm in &item.arms[..sorted_len] {
+            if let Pat::Wild(wild) = &arm.pat {
+                self.push_error(Error::new_spanned(
+                    wild,
+                    "`_` should come after all the sorted arms",
+                ));
+                continue;
+            }
+            match ArmKey::new(&arm.pat, options) {
+                Ok(arm_key) => arm_keys.push(arm_key),
+                Err(error) => {
+                    // The order of the remaining arms cannot be judged.
+                    self.push_error(error);
+                    return;
+                }
+            }
+            // The alternatives of `A | B` are sorted among themselves, in the
+            // same order as the arms, while the arm takes the place of its
+            // first alternative.
+            if let Pat::Or(or) = &arm.pat {
+                let alternatives: syn::Result<Vec<ArmKey>> = or
+                    .cases
+                    .iter()
+                    .map(|case| ArmKey::new(case, options))
+                    .collect();
+                match (alternatives, &options.as_declared) {
+                    (Ok(alternatives), Some(enum_path)) => {
+                        self.check_declared_order(enum_path, alternatives)
+                    }
+                    (Ok(alternatives), None) => self.check_order(&alternatives),
+                    (Err(error), _) => self.push_error(error),
+                }
+            }
+        }
+        if let Some(enum_path) = &options.as_declared {
+            self.check_declared_order(enum_path, arm_keys);
+            return;
+        }
+        if options.grouped {
+            self.check_groups(arm_keys, options.collation);
+        } else {
+            self.check_order(&arm_keys);
+        }
+    }
+
+    // Arms over the same enum, as told by the path before the variant name,
+    // have to be written together. Each group is sorted on its own and the
+    // groups are sorted by their path.
+    fn check_groups(&mut self, arm_keys: Vec<ArmKey>, collation: Collation) {
+        let mut groups: Vec<(Vec<String>, Vec<ArmKey>)> = vec![];
+        for arm_key in arm_keys {
+            let prefix = arm_key
+                .path
+                .as_ref()
+                .map(ArmPath::prefix)
+                .unwrap_or_default();
+            match groups.iter().position(|(seen, _)| *seen == prefix) {
+                Some(group) if group == groups.len() - 1 => groups[group].1.push(arm_key),
+                Some(_) => self.push_error(Error::new_spanned(
+                    &arm_key.tokens,
+                    format!(
+                        "{} should be next to the other {} arms",
+                        arm_key.display,
+                        group_name(&prefix)
+                    ),
+                )),
+                None => groups.push((prefix, vec![arm_key])),
+            }
+        }
+        let keys: Vec<Vec<sorted_order::Key>> = groups
+            .iter()
+            .map(|(prefix, _)| {
+                prefix
+                    .iter()
+                    .map(|segment| collation.key(segment))
+                    .collect()
+            })
+            .collect();
+        let names: Vec<String> = groups
+            .iter()
+            .map(|(prefix, _)| format!("{} arms", group_name(prefix)))
+            .collect();
+        if let Some(error) = order_errors(&keys, &names, None, |i, message| {
+            Error::new_spanned(&groups[i].1[0].tokens, message)
+        }) {
+            self.push_error(error);
+        }
+        for (_, group) in &groups {
+            self.check_order(group);
+        }
+    }
+
+    fn check_declared_order(&mut self, enum_path: &Path, arm_keys: Vec<ArmKey>) {
+        let arm_paths: syn::Result<Vec<ArmPath>> = arm_keys
+            .into_iter()
+            .map(|arm_key| {
+                arm_key.path.ok_or_else(|| {
+                    Error::new_spanned(
+                        arm_key.tokens,
+                        "`as_declared` expects every arm to match a variant",
+                    )
+                })
+            })
+            .collect();
+        match arm_paths {
+            Ok(arm_paths) => {
+                let check = declared_order_check(enum_path, &arm_paths);
+                self.declared_order_checks.push(check);
+            }
+            Err(error) => self.push_error(error),
+        }
+    }
+
+    fn check_order(&mut self, arm_keys: &[ArmKey]) {
+        let keys: Vec<&PatKey> = arm_keys.iter().map(|arm_key| &arm_key.key).collect();
+        let names: Vec<String> = arm_keys
+            .iter()
+            .map(|arm_key| arm_key.display.clone())
+            .collect();
+        if let Some(error) = order_errors(&keys, &names, None, |i, message| {
+            Error::new_spanned(&arm_keys[i].tokens, message)
+        }) {
+            self.push_error(error);
+        }
+    }
+
+    fn push_error(&mut self, error: Error) {
+        match &mut self.error {
+            Some(errors) => errors.combine(error),
+            None => self.error = Some(error),
+        }
+    }
+}
+
+fn group_name(prefix: &[String]) -> String {
+    if prefix.is_empty() {
+        "unqualified".to_owned()
+    } else {
+        prefix.join("::")
+    }
+}
+
+// One error for every misplaced item, each at the item's own span. The first
+// also lists the whole sorted order and the fewest moves reaching it, so that
+// the fix can be applied in one go.
+fn order_errors<K: Ord>(
+    keys: &[K],
+    names: &[String],
+    section: Option<&str>,
+    error_at: impl Fn(usize, String) -> Error,
+) -> Option<Error> {
+    let mut errors: Option<Error> = None;
+    for (i, before) in sorted_order::violations(keys) {
+        let mut message = format!("{} should sort before {}", names[i], names[before]);
+        if let Some(section) = section {
+            message.push_str(&format!(" in section `{}`", section));
+        }
+        if errors.is_none() {
+            let sorted: Vec<&str> = sorted_order::sorted_order(keys)
+                .into_iter()
+                .map(|i| names[i].as_str())
+                .collect();
+            let moves: Vec<String> = sorted_order::minimal_moves(keys)
+                .into_iter()
+                .map(|m| match m.before {
+                    Some(before) => format!("move {} before {}", names[m.item], names[before]),
+                    None => format!("move {} to the end", names[m.item]),
+                })
+                .collect();
+            message.push_str(&format!(
+                "\nsorted order: {}\nfewest moves: {}",
+                sorted.join(", "),
+                moves.join(", ")
+            ));
+        }
+        let error = error_at(i, message);
+        match &mut errors {
+            Some(errors) => errors.combine(error),
+            None => errors = Some(error),
+        }
+    }
+    errors
+}
+
+fn prepend_checks(block: &mut syn::Block, checks: Vec<TokenStream2>) {
+    let checks = checks
+        .into_iter()
+        .map(|check| Stmt::Item(Item::Verbatim(check)));
+    block.stmts.splice(0..0, checks);
+}
+
+// Name of the hidden const through which a #[sorted(publish_order)] enum
+// publishes the declaration order of its variants.
+fn declared_order_ident(enum_ident: &Ident) -> Ident {
+    format_ident!("__SORTED_DECLARED_ORDER_{}", enum_ident)
+}
+
+// A constant assertion that the arms follow the order published by the enum
+// at `enum_path`. Each pair of neighbouring arms is checked on its own so that
+// a failure points at the arm that is out of place.
+fn declared_order_check(enum_path: &Path, arm_paths: &[ArmPath]) -> TokenStream2 {
+    let mut order_path = enum_path.clone();
+    let last = order_path.segments.last_mut().unwrap();
+    last.ident = declared_order_ident(&last.ident);
+    last.arguments = syn::PathArguments::None;
+    let enum_name = ArmPath::from(enum_path.clone()).display_string();
+    let checks = arm_paths.windows(2).map(|pair| {
+        let prev = pair[0].get_ident().to_string();
+        let this = pair[1].get_ident().to_string();
+        let message = format!(
+            "{} should sort before {} in the declaration order of {}",
+            pair[1].display_string(),
+            pair[0].display_string(),
+            enum_name
+        );
+        quote_spanned! {pair[1].path.span()=>
+            ::core::assert!(position(#prev) <= position(#this), #message);
+        }
+    });
+    quote! {
+        const _: () = {
+            const fn position(name: &str) -> usize {
+                let declared: &[&str] = #order_path;
+                let mut i = 0;
+                while i < declared.len() {
+                    let candidate = declared[i].as_bytes();
+                    let name = name.as_bytes();
+                    if candidate.len() == name.len() {
+                        let mut j = 0;
+                        while j < name.len() && candidate[j] == name[j] {
+                            j += 1;
+                        }
+                        if j == name.len() {
+                            return i;
+                        }
+                    }
+                    i += 1;
+                }
+                declared.len()
+            }
+            #(#checks)*
+        };
+    }
 }
 
 impl CheckInput {
     fn build(self) -> TokenStream {
         let Self { mut syntax_tree } = self;
         let mut checker = Checker::default();
-        checker.visit_item_fn_mut(&mut syntax_tree);
+        checker.visit_item_mut(&mut syntax_tree);
         let err_quote = if let Some(err) = checker.error {
             err.into_compile_error()
         } else {
             quote!()
         };
+        // Matches outside of any function body, such as in the initializer of a
+        // const, are checked next to the item. Inside a module the checks go
+        // into the module itself, so that the `as_declared` paths resolve the
+        // same way as the matches using them.
+        let mut declared_order_checks = checker.declared_order_checks;
+        if let Item::Mod(ItemMod {
+            content: Some((_, items)),
+            ..
+        }) = &mut syntax_tree
+        {
+            for check in declared_order_checks.drain(..) {
+                items.push(Item::Verbatim(check));
+            }
+        }
         quote!(
             #syntax_tree
             #err_quote
+            #(#declared_order_checks)*
         )
         .into()
     }
 }
 
 struct SortedInput {
-    item: ItemEnum,
+    item: Item,
+    // The names whose order is checked. A `use` item has one list for each
+    // brace group and an enum one for each section, every other item a single
+    // list.
+    lists: Vec<List>,
+    // Bad section markers, reported along with the item.
+    error: Option<Error>,
+}
+
+// Names sorted among themselves.
+struct List {
+    // Set for the variants from a `#[sorted(section = "...")]` marker on.
+    section: Option<String>,
+    entries: Vec<Entry>,
+}
+
+impl FromIterator<Entry> for List {
+    fn from_iter<I: IntoIterator<Item = Entry>>(entries: I) -> Self {
+        Self {
+            section: None,
+            entries: entries.into_iter().collect(),
+        }
+    }
+}
+
+// One name checked by #[sorted] on an item.
+struct Entry {
+    // What is compared, which for a string literal is its value.
+    name: String,
+    // What diagnostics show.
+    display: String,
+    span: Span,
+    // Compared before the name. Only `use` items have entries of more than
+    // one class.
+    class: u8,
+}
+
+impl From<&Ident> for Entry {
+    fn from(ident: &Ident) -> Self {
+        Self {
+            name: ident.to_string(),
+            display: ident.to_string(),
+            span: ident.span(),
+            class: 0,
+        }
+    }
 }
 
 impl Parse for SortedInput {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let item: Item = input.parse()?;
-        let Item::Enum(item) = item else {
-            return Err(Error::new(input.span(), "expected enum or match expression"));
+        let mut item: Item = input.parse()?;
+        let mut error = None;
+        let lists = match &mut item {
+            Item::Enum(item) => sections(item, &mut error),
+            Item::Struct(ItemStruct {
+                fields: Fields::Named(fields),
+                ..
+            }) => vec![fields
+                .named
+                .iter()
+                .filter_map(|field| field.ident.as_ref().map(Entry::from))
+                .collect()],
+            Item::Struct(item) => {
+                return Err(Error::new_spanned(
+                    &item.fields,
+                    "expected a struct with named fields",
+                ))
+            }
+            Item::Impl(item) => vec![item
+                .items
+                .iter()
+                .filter_map(|item| match item {
+                    ImplItem::Fn(method) => Some(Entry::from(&method.sig.ident)),
+                    _ => None,
+                })
+                .collect()],
+            Item::Trait(item) => vec![item
+                .items
+                .iter()
+                .filter_map(|item| match item {
+                    TraitItem::Const(item) => Some(Entry::from(&item.ident)),
+                    TraitItem::Fn(item) => Some(Entry::from(&item.sig.ident)),
+                    TraitItem::Type(item) => Some(Entry::from(&item.ident)),
+                    _ => None,
+                })
+                .collect()],
+            Item::Use(item) => {
+                let mut lists = vec![];
+                use_groups(&item.tree, &mut lists);
+                if lists.is_empty() {
+                    return Err(Error::new_spanned(
+                        &item.tree,
+                        "expected a use group such as `use a::{b, c}`",
+                    ));
+                }
+                lists
+            }
+            Item::Const(ItemConst { expr, .. }) | Item::Static(ItemStatic { expr, .. }) => {
+                vec![string_array(expr)?]
+            }
+            _ => {
+                return Err(Error::new(
+                    input.span(),
+                    "expected enum, struct, impl, trait, use, const, static or match \
+                     expression",
+                ))
+            }
         };
-        Ok(Self { item })
+        Ok(Self { item, lists, error })
+    }
+}
+
+// The variants of an enum, split into sections at each variant marked with
+// `#[sorted(section = "name")]`. The markers are removed from the enum, and
+// those that cannot be parsed are added to `errors`.
+fn sections(item: &mut ItemEnum, errors: &mut Option<Error>) -> Vec<List> {
+    let mut lists = vec![List {
+        section: None,
+        entries: vec![],
+    }];
+    for variant in &mut item.variants {
+        let mut section = None;
+        variant.attrs.retain(|attr| {
+            if !attr.path().is_ident("sorted") {
+                return true;
+            }
+            let parsed = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("section") {
+                    let name: LitStr = meta.value()?.parse()?;
+                    section = Some(name.value());
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `section = \"...\"`"))
+                }
+            });
+            if let Err(error) = parsed {
+                match errors {
+                    Some(errors) => errors.combine(error),
+                    None => *errors = Some(error),
+                }
+            }
+            false
+        });
+        if section.is_some() {
+            lists.push(List {
+                section,
+                entries: vec![],
+            });
+        }
+        lists
+            .last_mut()
+            .unwrap()
+            .entries
+            .push(Entry::from(&variant.ident));
+    }
+    lists
+}
+
+// The items of every brace group in a use tree, such as `b` and `c` in
+// `use a::{b, c}`, each named by its first segment and ordered the way rustfmt
+// orders them.
+fn use_groups(tree: &UseTree, lists: &mut Vec<List>) {
+    match tree {
+        UseTree::Path(path) => use_groups(&path.tree, lists),
+        UseTree::Group(group) => {
+            let entries = group
+                .items
+                .iter()
+                .filter_map(|tree| match tree {
+                    UseTree::Path(path) => Some(use_entry(&path.ident)),
+                    UseTree::Name(name) => Some(use_entry(&name.ident)),
+                    UseTree::Rename(rename) => Some(use_entry(&rename.ident)),
+                    UseTree::Glob(glob) => Some(Entry {
+                        name: "*".to_owned(),
+                        display: "*".to_owned(),
+                        span: glob.star_token.span,
+                        class: 6,
+                    }),
+                    UseTree::Group(_) => None,
+                })
+                .collect();
+            lists.push(entries);
+            for tree in &group.items {
+                use_groups(tree, lists);
+            }
+        }
+        UseTree::Name(_) | UseTree::Rename(_) | UseTree::Glob(_) => {}
     }
 }
 
+// rustfmt puts `self`, `super` and `crate` first, then names starting with a
+// lowercase letter, CamelCase names, UPPER_SNAKE_CASE names and finally a
+// glob, comparing names as strings within each of these classes.
+fn use_entry(ident: &Ident) -> Entry {
+    let name = ident.to_string();
+    let upper_snake = |name: &str| {
+        name.chars()
+            .all(|ch| ch.is_uppercase() || ch.is_numeric() || ch == '_')
+    };
+    let class = match name.as_str() {
+        "self" => 0,
+        "super" => 1,
+        "crate" => 2,
+        _ if !name.starts_with(char::is_uppercase) && !upper_snake(&name) => 3,
+        _ if !upper_snake(&name) => 4,
+        _ => 5,
+    };
+    Entry {
+        class,
+        ..Entry::from(ident)
+    }
+}
+
+// The string literals of a `const` or `static` array or slice, such as a
+// keyword table that is binary searched.
+fn string_array(expr: &Expr) -> syn::Result<List> {
+    let array = match expr {
+        Expr::Reference(reference) => &*reference.expr,
+        expr => expr,
+    };
+    let Expr::Array(array) = array else {
+        return Err(Error::new_spanned(
+            expr,
+            "expected an array of string literals",
+        ));
+    };
+    array
+        .elems
+        .iter()
+        .map(|elem| match elem {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(lit), ..
+            }) => Ok(Entry {
+                name: lit.value(),
+                display: lit.token().to_string(),
+                span: lit.span(),
+                class: 0,
+            }),
+            _ => Err(Error::new_spanned(elem, "expected a string literal")),
+        })
+        .collect()
+}
+
 impl SortedInput {
-    fn build(self) -> TokenStream {
+    fn build(self, options: SortOptions) -> TokenStream {
         // Vis
-        let item = self.item;
-        let ItemEnum { variants, .. } = &item;
-        let mut seen_variants: Vec<&syn::Variant> = vec![];
-        let mut error = quote!();
-        for variant in variants {
-            if let Some(&last) = seen_variants.last() {
-                if variant.ident < last.ident {
-                    let before = seen_variants
-                        .iter()
-                        .find(|seen| seen.ident > variant.ident)
-                        .unwrap();
-                    error = Error::new(
-                        variant.ident.span(),
-                        &format!("{} should sort before {}", variant.ident, before.ident),
-                    )
-                    .into_compile_error();
-                    break;
+        let Self {
+            item,
+            lists,
+            error: mut errors,
+        } = self;
+        for (span, option) in &options.match_only {
+            let error = Error::new(
+                *span,
+                format!("`{}` only applies to match expressions", option),
+            );
+            match &mut errors {
+                Some(errors) => errors.combine(error),
+                None => errors = Some(error),
+            }
+        }
+        // String tables are checked so that they can be binary searched, which
+        // compares plain strings.
+        if let Item::Const(_) | Item::Static(_) = item {
+            for (span, option) in &options.collation_options {
+                let error = Error::new(
+                    *span,
+                    format!(
+                        "`{}` does not apply to string tables, which are binary searched \
+                         in plain string order",
+                        option
+                    ),
+                );
+                match &mut errors {
+                    Some(errors) => errors.combine(error),
+                    None => errors = Some(error),
+                }
+            }
+        }
+        if !matches!(item, Item::Enum(_)) {
+            for (span, option) in &options.enum_only {
+                let error = Error::new(*span, format!("`{}` only applies to enums", option));
+                match &mut errors {
+                    Some(errors) => errors.combine(error),
+                    None => errors = Some(error),
+                }
+            }
+        }
+        if let Some(span) = options.metadata {
+            for error in metadata_errors(&item, span, options.collation) {
+                match &mut errors {
+                    Some(errors) => errors.combine(error),
+                    None => errors = Some(error),
                 }
             }
-            seen_variants.push(variant);
         }
+        // An enum publishing its order is not sorted, so there is nothing to
+        // check.
+        let lists = if options.publish_order { vec![] } else { lists };
+        for List { section, entries } in &lists {
+            let keys: Vec<(u8, sorted_order::Key)> = entries
+                .iter()
+                .map(|entry| (entry.class, options.collation.key(&entry.name)))
+                .collect();
+            let names: Vec<String> = entries.iter().map(|entry| entry.display.clone()).collect();
+            let error = order_errors(&keys, &names, section.as_deref(), |i, message| {
+                Error::new(entries[i].span, message)
+            });
+            match (&mut errors, error) {
+                (Some(errors), Some(error)) => errors.combine(error),
+                (None, error) => errors = error,
+                (_, None) => {}
+            }
+        }
+        let metadata = match (&item, options.metadata, &errors) {
+            (Item::Enum(item), Some(_), None) => metadata(item),
+            _ => quote!(),
+        };
+        let error = errors.map(Error::into_compile_error);
+        // Published for matches checked with `#[sorted(as_declared = ...)]`.
+        let declared_order = match &item {
+            Item::Enum(ItemEnum {
+                vis,
+                ident,
+                variants,
+                ..
+            }) if options.publish_order => {
+                let declared_order = declared_order_ident(ident);
+                let names = variants.iter().map(|variant| variant.ident.to_string());
+                quote! {
+                    #[doc(hidden)]
+                    #[allow(dead_code, non_upper_case_globals)]
+                    #vis const #declared_order: &[&str] = &[#(#names),*];
+                }
+            }
+            _ => quote!(),
+        };
 
         quote!(
             #item
             #error
+            #declared_order
+            #metadata
         )
         .into()
     }
 }
 
+// Reasons `#[sorted(metadata)]` cannot be generated for the enum `item`.
+fn metadata_errors(item: &Item, span: Span, collation: Collation) -> Vec<Error> {
+    let Item::Enum(item) = item else {
+        return vec![];
+    };
+    let mut errors = vec![];
+    // `from_name` searches with the ordering of `str`, which only agrees with
+    // the checked order when names compare as plain strings.
+    if collation.natural || collation.case_insensitive {
+        errors.push(Error::new(
+            span,
+            "`metadata` cannot be combined with `natural` or `case_insensitive`, \
+             since `from_name` searches names as plain strings",
+        ));
+    }
+    for variant in &item.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            errors.push(Error::new_spanned(
+                &variant.fields,
+                "`metadata` needs unit variants, which `from_name` can construct",
+            ));
+        }
+    }
+    errors
+}
+
+// The items generated by `#[sorted(metadata)]`. The names are sorted here, as
+// an enum split into sections is not sorted as a whole, and double as a table
+// to binary search.
+fn metadata(item: &ItemEnum) -> TokenStream2 {
+    let ItemEnum {
+        vis,
+        ident,
+        generics,
+        variants,
+        ..
+    } = item;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut variants: Vec<&Ident> = variants.iter().map(|variant| &variant.ident).collect();
+    variants.sort_by_key(|variant| variant.to_string());
+    let names: Vec<String> = variants.iter().map(ToString::to_string).collect();
+    let indices = 0..variants.len();
+    quote! {
+        #[allow(dead_code)]
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// The names of the variants, in sorted order.
+            #vis const VARIANT_NAMES: &'static [&'static str] = &[#(#names),*];
+
+            /// The variant with the given name, found by binary search.
+            #vis fn from_name(name: &str) -> ::core::option::Option<Self> {
+                match Self::VARIANT_NAMES.binary_search(&name) {
+                    #(::core::result::Result::Ok(#indices) => {
+                        ::core::option::Option::Some(Self::#variants)
+                    })*
+                    _ => ::core::option::Option::None,
+                }
+            }
+
+            /// The name of this variant.
+            #vis fn name(&self) -> &'static str {
+                match *self {
+                    #(Self::#variants => #names,)*
+                }
+            }
+        }
+    }
+}
+
 // fn handle_sorted(input: TokenStream) -> Result<TokenStream2, Error> {
 //     let sorted_input = parse_macro_input!(input as SortedInput);
 //     let item = sorted_input.item;
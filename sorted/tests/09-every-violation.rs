@@ -0,0 +1,43 @@
+// Every misplaced variant or arm is reported, each once and at its own span,
+// across all enums and every #[sorted] match in a function, including matches
+// nested inside the arms of another.
+
+use sorted::sorted;
+
+#[sorted]
+pub enum Conference {
+    RustConf,
+    RustBeltRust,
+    RustFest,
+    RustLatam,
+    EuroRust,
+}
+
+#[sorted::check]
+fn f(conference: Conference, year: u32) -> &'static str {
+    use self::Conference::*;
+
+    let mut name = "";
+    #[sorted]
+    match conference {
+        RustBeltRust => name = "RustBeltRust",
+        RustLatam => name = "RustLatam",
+        RustFest => name = "RustFest",
+        EuroRust => name = "EuroRust",
+        RustConf => {
+            #[sorted]
+            match year.checked_sub(2015) {
+                Some(_) => name = "RustConf",
+                None => {}
+            }
+        }
+    }
+
+    #[sorted]
+    match Some(name) {
+        Some(name) => name,
+        None => "",
+    }
+}
+
+fn main() {}
@@ -0,0 +1,48 @@
+// #[sorted(natural)] compares runs of digits by value, so that `Http2` sorts
+// before `Http10`, and #[sorted(case_insensitive)] ignores case, so names that
+// only differ in case may come in either order. Both can be combined and apply
+// to match expressions as well.
+
+use sorted::sorted;
+
+#[sorted(natural)]
+pub enum Version {
+    Http1,
+    Http2,
+    Http10,
+    Http11,
+}
+
+#[sorted(case_insensitive)]
+#[allow(non_camel_case_types)]
+pub enum Fruit {
+    apple,
+    Apple,
+    Banana,
+    cherry,
+}
+
+#[sorted(natural, case_insensitive)]
+#[allow(non_camel_case_types)]
+pub enum Port {
+    com2,
+    COM10,
+}
+
+#[sorted::check]
+fn describe(version: Version) -> &'static str {
+    use self::Version::*;
+
+    #[sorted(natural)]
+    match version {
+        Http1 => "HTTP/1.0",
+        Http2 => "HTTP/2",
+        Http10 => "unreleased",
+        Http11 => "unreleased",
+    }
+}
+
+fn main() {
+    assert_eq!(describe(Version::Http2), "HTTP/2");
+    let _ = (Fruit::apple, Fruit::Banana, Fruit::cherry, Port::com2, Port::COM10);
+}
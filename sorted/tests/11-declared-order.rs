@@ -0,0 +1,76 @@
+// Matches over an enum whose variants are not alphabetical, such as one that
+// follows a protocol's own numbering, can instead be checked against the
+// enum's declaration order with #[sorted(as_declared = "path::Enum")].
+//
+// Such an enum is marked #[sorted(publish_order)]. Its variants are left in
+// the order they are written, and the macro publishes that order for the
+// matches to be checked against. Each check expands to a constant assertion
+// at the start of the function body, which also works for methods. The
+// alternatives of an or-pattern follow the declaration order too.
+
+mod frames {
+    use sorted::sorted;
+
+    #[sorted(publish_order)]
+    pub enum Frame {
+        Data,
+        Headers,
+        Priority,
+        Reset,
+        Settings,
+        PushPromise,
+        Ping,
+        GoAway,
+        WindowUpdate,
+        Continuation,
+    }
+}
+
+use frames::Frame;
+
+#[sorted::check]
+fn code(frame: Frame) -> u8 {
+    #[sorted(as_declared = "frames::Frame")]
+    match frame {
+        Frame::Data => 0x0,
+        Frame::Headers => 0x1,
+        Frame::Priority => 0x2,
+        Frame::Reset => 0x3,
+        Frame::Settings => 0x4,
+        Frame::PushPromise => 0x5,
+        Frame::Ping => 0x6,
+        Frame::GoAway => 0x7,
+        Frame::WindowUpdate => 0x8,
+        Frame::Continuation => 0x9,
+    }
+}
+
+#[sorted::check]
+fn is_control(frame: Frame) -> bool {
+    #[sorted(as_declared = "frames::Frame")]
+    match frame {
+        Frame::Reset | Frame::Settings => true,
+        Frame::Ping | Frame::GoAway | Frame::WindowUpdate => true,
+        _ => false,
+    }
+}
+
+pub struct Stream;
+
+impl Stream {
+    #[sorted::check]
+    pub fn weight(&self, frame: Frame) -> u8 {
+        #[sorted(as_declared = "frames::Frame")]
+        match frame {
+            Frame::Headers => 16,
+            Frame::Priority => 32,
+            _ => 0,
+        }
+    }
+}
+
+fn main() {
+    assert_eq!(code(Frame::Continuation), 0x9);
+    assert!(is_control(Frame::GoAway));
+    assert_eq!(Stream.weight(Frame::Priority), 32);
+}
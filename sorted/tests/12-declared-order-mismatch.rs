@@ -0,0 +1,52 @@
+// An arm or an alternative out of the declaration order fails the constant
+// assertion at that arm or alternative. Unknown options are rejected,
+// `as_declared` makes no sense on the enum itself, and only an enum can
+// publish its order.
+
+use sorted::sorted;
+
+#[sorted(publish_order)]
+pub enum Level {
+    Trace,
+    Debug,
+    Info,
+    Error,
+}
+
+#[sorted::check]
+fn rank(level: Level) -> u8 {
+    #[sorted(as_declared = "Level")]
+    match level {
+        Level::Trace => 0,
+        Level::Debug => 1,
+        Level::Error => 3,
+        Level::Info => 2,
+    }
+}
+
+#[sorted::check]
+fn is_quiet(level: Level) -> bool {
+    #[sorted(as_declared = "Level")]
+    match level {
+        Level::Debug | Level::Trace => true,
+        _ => false,
+    }
+}
+
+#[sorted(reverse)]
+pub enum Reversed {
+    B,
+    A,
+}
+
+#[sorted(as_declared = "Level")]
+pub enum Severity {
+    A,
+}
+
+#[sorted(publish_order)]
+pub struct Config {
+    level: Level,
+}
+
+fn main() {}
@@ -0,0 +1,85 @@
+// Besides enums, #[sorted] checks the named fields of a struct, the methods of
+// an impl block, the items of a trait, the names in each brace group of a use
+// item, and the string literals of a const or static array. The last is meant
+// for tables that are binary searched at runtime.
+//
+// Use groups are expected in the order rustfmt writes them: `self` first, then
+// lowercase names, CamelCase names and UPPER_SNAKE_CASE names.
+
+use sorted::sorted;
+
+#[sorted]
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    io::{self, Read},
+};
+
+mod limits {
+    pub mod defaults {}
+    pub struct Limits;
+    pub const MAX_RETRIES: u32 = 3;
+    pub fn check() {}
+}
+
+#[allow(unused_imports)]
+mod rustfmt_order {
+    use sorted::sorted;
+
+    #[sorted]
+    use super::limits::{self, check, defaults, Limits, MAX_RETRIES};
+
+    #[sorted]
+    use std::collections::{hash_map, BTreeMap, HashMap};
+}
+
+#[sorted]
+pub struct Config {
+    name: String,
+    retries: u32,
+    timeout: u64,
+}
+
+#[sorted]
+impl Config {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    const DEFAULT_RETRIES: u32 = 3;
+
+    pub fn retries(&self) -> u32 {
+        self.retries.max(Self::DEFAULT_RETRIES)
+    }
+}
+
+#[sorted]
+pub trait Source {
+    type Error;
+    const NAME: &'static str;
+    fn read(&mut self) -> Result<Vec<u8>, Self::Error>;
+}
+
+#[sorted]
+const KEYWORDS: &[&str] = &["as", "break", "const", "continue", "crate", "else"];
+
+#[sorted]
+static UNITS: [&str; 3] = ["B", "MB", "kB"];
+
+fn is_keyword(word: &str) -> bool {
+    KEYWORDS.binary_search(&word).is_ok()
+}
+
+fn main() {
+    let _: (BTreeMap<u8, u8>, HashMap<u8, u8>, fmt::Error) = Default::default();
+    let _ = io::empty().read(&mut []);
+    assert!(UNITS.binary_search(&"MB").is_ok());
+    let config = Config {
+        name: String::new(),
+        retries: 0,
+        timeout: 0,
+    };
+    assert_eq!(config.retries(), 3);
+    assert!(config.name().is_empty() && config.timeout == 0);
+    assert!(is_keyword("crate") && !is_keyword("fn"));
+}
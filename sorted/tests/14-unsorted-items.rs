@@ -0,0 +1,44 @@
+// Out-of-order names in any of the items #[sorted] accepts are reported at the
+// name. An array that is not made of string literals is rejected, and so is a
+// collation other than plain string order on a table meant to be binary
+// searched.
+
+use sorted::sorted;
+
+#[sorted]
+#[allow(unused_imports)]
+use std::{fmt, collections::HashMap, io::{Read, self}};
+
+#[sorted]
+pub struct Config {
+    timeout: u64,
+    name: String,
+}
+
+#[sorted]
+impl Config {
+    fn timeout(&self) -> u64 {
+        self.timeout
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[sorted]
+pub trait Source {
+    fn read(&mut self);
+    fn close(&mut self);
+}
+
+#[sorted]
+const KEYWORDS: &[&str] = &["as", "const", "break"];
+
+#[sorted]
+const SIZES: [u32; 2] = [1, 2];
+
+#[sorted(case_insensitive)]
+static UNITS: [&str; 3] = ["B", "kB", "MB"];
+
+fn main() {}
@@ -0,0 +1,72 @@
+// Beyond paths, #[sorted] matches may use or-patterns, whose alternatives are
+// sorted among themselves and place the arm by the first one, `name @ pattern`
+// bindings, integer, char and string literals compared by value, ranges
+// compared by their start, and tuples compared element by element.
+
+#[derive(Clone, Copy)]
+pub enum Token {
+    Comma,
+    Ident,
+    Literal,
+    Semi,
+}
+
+#[sorted::check]
+fn classify(token: Token, code: i32, c: char, name: &str) -> u32 {
+    use self::Token::*;
+
+    let kind = #[sorted] match token {
+        Comma | Semi => 0,
+        t @ Ident => t as u32,
+        Literal => 2,
+    };
+
+    let status = #[sorted] match code {
+        -1 => 0,
+        2 => 1,
+        10 => 2,
+        100..=199 => 3,
+        200..300 => 4,
+        _ => 5,
+    };
+
+    let letter = #[sorted] match c {
+        'A'..='Z' => 0,
+        'a' => 1,
+        'z' => 2,
+        _ => 3,
+    };
+
+    let keyword = #[sorted] match name {
+        "break" => 0,
+        "fn" => 1,
+        "loop" => 2,
+        _ => 3,
+    };
+
+    let pair = #[sorted] match (token, code > 0) {
+        (Comma, false) => 0,
+        (Comma, true) => 1,
+        (Ident, _) => 2,
+        (Literal | Semi, _) => 3,
+    };
+
+    kind + status + letter + keyword + pair
+}
+
+#[sorted::check]
+fn width(value: u128) -> u32 {
+    #[sorted]
+    match value {
+        0 => 0,
+        170141183460469231731687303715884105727 => 127,
+        170141183460469231731687303715884105728 => 128,
+        340282366920938463463374607431768211455 => 128,
+        _ => 64,
+    }
+}
+
+fn main() {
+    assert_eq!(classify(Token::Ident, 150, 'a', "fn"), 1 + 3 + 1 + 1 + 2);
+    assert_eq!(width(u128::MAX), 128);
+}
@@ -0,0 +1,47 @@
+// Out-of-order alternatives, literals, ranges and tuples are reported at the
+// pattern that is out of place.
+
+pub enum Token {
+    Comma,
+    Ident,
+    Semi,
+}
+
+#[sorted::check]
+fn classify(token: Token, code: i32) -> u32 {
+    use self::Token::*;
+
+    let kind = #[sorted] match token {
+        Semi | Comma => 0,
+        Ident => 1,
+    };
+
+    let status = #[sorted] match code {
+        10 => 0,
+        -1 => 1,
+        200..300 => 2,
+        100..=199 => 3,
+        _ => 4,
+    };
+
+    let pair = #[sorted] match (kind, status) {
+        (1, _) => 0,
+        (0, 1) => 1,
+        (0, _) => 2,
+        _ => 3,
+    };
+
+    pair
+}
+
+#[sorted::check]
+fn width(value: u128) -> u32 {
+    #[sorted]
+    match value {
+        340282366920938463463374607431768211455 => 128,
+        170141183460469231731687303715884105727 => 127,
+        _ => 64,
+    }
+}
+
+fn main() {}
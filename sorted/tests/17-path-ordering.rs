@@ -0,0 +1,45 @@
+// By default arms compare by the last segment of their path only. With
+// #[sorted(full_path)] whole paths are compared instead, and with
+// #[sorted(grouped)] the arms sharing a prefix, such as constants from one
+// module or variants of one enum, are written together and sorted among
+// themselves while the groups are sorted by that prefix.
+
+pub mod client {
+    pub const BAD_REQUEST: u16 = 400;
+    pub const NOT_FOUND: u16 = 404;
+}
+
+pub mod server {
+    pub const INTERNAL: u16 = 500;
+    pub const UNAVAILABLE: u16 = 503;
+}
+
+#[sorted::check]
+fn retry(status: u16) -> bool {
+    // `INTERNAL` would have to come before `NOT_FOUND` by name alone.
+    #[sorted(full_path)]
+    match status {
+        client::BAD_REQUEST => false,
+        client::NOT_FOUND => false,
+        server::INTERNAL => true,
+        server::UNAVAILABLE => true,
+        _ => false,
+    }
+}
+
+#[sorted::check]
+fn describe(status: u16) -> &'static str {
+    #[sorted(grouped)]
+    match status {
+        client::BAD_REQUEST => "bad request",
+        client::NOT_FOUND => "not found",
+        server::INTERNAL => "internal error",
+        server::UNAVAILABLE => "unavailable",
+        _ => "unknown",
+    }
+}
+
+fn main() {
+    assert!(retry(503) && !retry(404));
+    assert_eq!(describe(400), "bad request");
+}
@@ -0,0 +1,35 @@
+// In grouped mode an arm separated from the rest of its group is reported, as
+// is a group out of order, pointing at its first arm. The path options have no
+// meaning for items.
+
+use sorted::sorted;
+
+pub mod client {
+    pub const BAD_REQUEST: u16 = 400;
+    pub const NOT_FOUND: u16 = 404;
+}
+
+pub mod server {
+    pub const INTERNAL: u16 = 500;
+    pub const UNAVAILABLE: u16 = 503;
+}
+
+#[sorted::check]
+fn describe(status: u16) -> &'static str {
+    #[sorted(grouped)]
+    match status {
+        server::UNAVAILABLE => "unavailable",
+        client::NOT_FOUND => "not found",
+        client::BAD_REQUEST => "bad request",
+        server::INTERNAL => "internal error",
+        _ => "unknown",
+    }
+}
+
+#[sorted(full_path, grouped)]
+pub enum Status {
+    Client,
+    Server,
+}
+
+fn main() {}
@@ -0,0 +1,111 @@
+// Besides functions, #[sorted::check] can be placed on an impl block, a trait,
+// an inline module or a const or static item. It looks through everything
+// inside: methods, default trait methods, nested functions and closures in
+// initialisers. Large matches tend to live in trait impls, so checking the
+// whole impl saves repeating the attribute on every method.
+
+use sorted::sorted;
+
+use std::fmt::{self, Display};
+
+#[sorted]
+pub enum Level {
+    Debug,
+    Error,
+    Info,
+    Warn,
+}
+
+#[sorted::check]
+impl Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = #[sorted]
+        match self {
+            Level::Debug => "debug",
+            Level::Error => "error",
+            Level::Info => "info",
+            Level::Warn => "warn",
+        };
+        f.write_str(name)
+    }
+}
+
+#[sorted::check]
+trait Severity {
+    fn level(&self) -> Level;
+
+    fn is_loud(&self) -> bool {
+        #[sorted]
+        match self.level() {
+            Level::Error | Level::Warn => true,
+            _ => false,
+        }
+    }
+}
+
+#[sorted::check]
+const PARSE: fn(&str) -> Option<Level> = |name| {
+    #[sorted]
+    match name {
+        "debug" => Some(Level::Debug),
+        "error" => Some(Level::Error),
+        "info" => Some(Level::Info),
+        "warn" => Some(Level::Warn),
+        _ => None,
+    }
+};
+
+#[sorted::check]
+mod codes {
+    use super::Level;
+
+    pub mod wire {
+        use sorted::sorted;
+
+        #[sorted(publish_order)]
+        pub enum Code {
+            Verbose,
+            Debug,
+            Info,
+        }
+    }
+
+    pub fn from_level(level: Level) -> Option<wire::Code> {
+        #[sorted]
+        match level {
+            Level::Debug => Some(wire::Code::Debug),
+            Level::Info => Some(wire::Code::Info),
+            _ => None,
+        }
+    }
+
+    pub fn rank(code: wire::Code) -> u8 {
+        #[sorted(as_declared = "wire::Code")]
+        match code {
+            wire::Code::Verbose => 0,
+            wire::Code::Debug => 1,
+            wire::Code::Info => 2,
+        }
+    }
+}
+
+struct Event(Level);
+
+impl Severity for Event {
+    fn level(&self) -> Level {
+        match self.0 {
+            Level::Debug => Level::Debug,
+            Level::Error => Level::Error,
+            Level::Info => Level::Info,
+            Level::Warn => Level::Warn,
+        }
+    }
+}
+
+fn main() {
+    assert_eq!(Level::Warn.to_string(), "warn");
+    assert!(Event(Level::Error).is_loud());
+    assert!(PARSE("info").is_some());
+    assert!(codes::from_level(Level::Debug).is_some());
+    assert_eq!(codes::rank(codes::wire::Code::Info), 2);
+}
@@ -0,0 +1,39 @@
+// Matches are checked wherever they appear inside the item carrying
+// #[sorted::check], including trait impl methods and closures inside a const.
+
+use sorted::sorted;
+
+use std::fmt::{self, Display};
+
+#[sorted]
+pub enum Level {
+    Debug,
+    Error,
+    Info,
+}
+
+#[sorted::check]
+impl Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(
+            #[sorted]
+            match self {
+                Level::Error => "error",
+                Level::Debug => "debug",
+                Level::Info => "info",
+            },
+        )
+    }
+}
+
+#[sorted::check]
+static PARSE: fn(&str) -> Option<Level> = |name| {
+    #[sorted]
+    match name {
+        "info" => Some(Level::Info),
+        "debug" => Some(Level::Debug),
+        _ => None,
+    }
+};
+
+fn main() {}
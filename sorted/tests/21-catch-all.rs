@@ -0,0 +1,48 @@
+// Arms with a guard are sorted by their pattern like any other arm, so the
+// same pattern may appear several times in a row with different guards.
+//
+// Whatever comes after the sorted arms has to catch everything left over. By
+// default that is `_`, possibly preceded by guarded `_` arms. With
+// #[sorted(catch_all = "binding")] a binding such as `other` may be used
+// instead, for when the arm needs the value it matched.
+
+use sorted::sorted;
+
+#[sorted]
+#[derive(Debug)]
+pub enum Token {
+    Comma,
+    Number(u32),
+    Word(&'static str),
+}
+
+#[sorted::check]
+fn describe(token: &Token) -> String {
+    #[sorted]
+    match token {
+        Token::Comma => "comma".to_owned(),
+        Token::Number(n) if *n > 9 => "large number".to_owned(),
+        Token::Number(_) => "digit".to_owned(),
+        Token::Word(w) if w.is_empty() => "empty word".to_owned(),
+        _ if cfg!(debug_assertions) => format!("{:?}", token),
+        _ => "word".to_owned(),
+    }
+}
+
+#[sorted::check]
+fn weight(n: u32) -> u32 {
+    #[sorted(catch_all = "binding")]
+    match n {
+        0 => 0,
+        1 => 10,
+        large if large > 1000 => 1000,
+        other => other * 2,
+    }
+}
+
+fn main() {
+    assert_eq!(describe(&Token::Number(12)), "large number");
+    assert_eq!(weight(1), 10);
+    assert_eq!(weight(5000), 1000);
+    assert_eq!(weight(3), 6);
+}
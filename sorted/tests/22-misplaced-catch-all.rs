@@ -0,0 +1,16 @@
+// A `_` arm followed by anything other than more catch-all arms is reported,
+// and the arms after it are still checked.
+
+#[sorted::check]
+fn rank(n: u32) -> u32 {
+    #[sorted]
+    match n {
+        0 => 0,
+        _ if n % 2 == 0 => 2,
+        3 => 3,
+        1 => 1,
+        _ => 4,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,44 @@
+// With #[sorted(metadata)] the enum also gets a table of its variant names
+// along with conversions between variants and their names. Since the macro has
+// already checked that the variants are sorted, `from_name` can look names up
+// by binary search.
+//
+//     impl Method {
+//         pub const VARIANT_NAMES: &'static [&'static str];
+//         pub fn from_name(name: &str) -> Option<Self>;
+//         pub fn name(&self) -> &'static str;
+//     }
+
+use sorted::sorted;
+
+#[sorted(metadata)]
+#[derive(Debug, PartialEq)]
+pub enum Method {
+    Delete,
+    Get,
+    Head,
+    Options,
+    Patch,
+    Post,
+    Put,
+}
+
+#[sorted(metadata)]
+enum Empty {}
+
+fn main() {
+    assert_eq!(
+        Method::VARIANT_NAMES,
+        ["Delete", "Get", "Head", "Options", "Patch", "Post", "Put"]
+    );
+    for name in Method::VARIANT_NAMES {
+        assert_eq!(Method::from_name(name).unwrap().name(), *name);
+    }
+    assert_eq!(Method::from_name("Post"), Some(Method::Post));
+    assert_eq!(Method::from_name("post"), None);
+    assert_eq!(Method::from_name("Trace"), None);
+    assert_eq!(Method::Head.name(), "Head");
+
+    assert!(Empty::VARIANT_NAMES.is_empty());
+    assert!(Empty::from_name("Get").is_none());
+}
@@ -0,0 +1,36 @@
+// `from_name` has to construct the variant it finds, so every variant needs to
+// be a unit variant. Its binary search compares names as plain strings, so the
+// enum cannot be sorted with `natural` or `case_insensitive` either.
+
+use sorted::sorted;
+
+#[sorted(metadata)]
+pub enum Shape {
+    Circle(f64),
+    Point,
+    Rect { width: f64, height: f64 },
+}
+
+#[sorted(metadata, natural)]
+pub enum Version {
+    Http1,
+    Http2,
+    Http10,
+}
+
+#[sorted(metadata)]
+pub struct Config {
+    name: String,
+}
+
+#[sorted::check]
+fn code(version: Version) -> u8 {
+    #[sorted(metadata, natural)]
+    match version {
+        Version::Http1 => 1,
+        Version::Http2 => 2,
+        Version::Http10 => 10,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,31 @@
+// Some enums are deliberately laid out in sections, such as client errors
+// followed by server errors. Marking the first variant of a section with
+// #[sorted(section = "name")] restarts the ordering check there, so that each
+// section is sorted on its own while the sections keep their written order.
+//
+// Variants before the first marker form a section of their own. The markers
+// are removed from the enum, and `metadata` still lists the names of all the
+// variants in sorted order.
+
+use sorted::sorted;
+
+#[sorted(metadata)]
+#[derive(Debug, PartialEq)]
+pub enum Status {
+    Continue,
+    SwitchingProtocols,
+    #[sorted(section = "client")]
+    BadRequest,
+    Forbidden,
+    NotFound,
+    #[sorted(section = "server")]
+    BadGateway,
+    Internal,
+    Unavailable,
+}
+
+fn main() {
+    assert_eq!(Status::from_name("Forbidden"), Some(Status::Forbidden));
+    assert_eq!(Status::VARIANT_NAMES[0], "BadGateway");
+    assert_eq!(Status::Unavailable.name(), "Unavailable");
+}
@@ -0,0 +1,23 @@
+// An ordering error inside a section names the section it occurs in.
+
+use sorted::sorted;
+
+#[sorted]
+pub enum Status {
+    #[sorted(section = "client")]
+    BadRequest,
+    NotFound,
+    Forbidden,
+    #[sorted(section = "server")]
+    Internal,
+    BadGateway,
+}
+
+#[sorted]
+pub enum Method {
+    Delete,
+    #[sorted(part = "safe")]
+    Get,
+}
+
+fn main() {}
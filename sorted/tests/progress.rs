@@ -9,4 +9,22 @@ fn tests() {
     t.compile_fail("tests/06-pattern-path.rs");
     t.compile_fail("tests/07-unrecognized-pattern.rs");
     t.pass("tests/08-underscore.rs");
+    t.compile_fail("tests/09-every-violation.rs");
+    t.pass("tests/10-sort-options.rs");
+    t.pass("tests/11-declared-order.rs");
+    t.compile_fail("tests/12-declared-order-mismatch.rs");
+    t.pass("tests/13-sorted-items.rs");
+    t.compile_fail("tests/14-unsorted-items.rs");
+    t.pass("tests/15-match-patterns.rs");
+    t.compile_fail("tests/16-unsorted-patterns.rs");
+    t.pass("tests/17-path-ordering.rs");
+    t.compile_fail("tests/18-bad-path-ordering.rs");
+    t.pass("tests/19-check-items.rs");
+    t.compile_fail("tests/20-check-items-unsorted.rs");
+    t.pass("tests/21-catch-all.rs");
+    t.compile_fail("tests/22-misplaced-catch-all.rs");
+    t.pass("tests/23-metadata.rs");
+    t.compile_fail("tests/24-metadata-errors.rs");
+    t.pass("tests/25-sections.rs");
+    t.compile_fail("tests/26-unsorted-section.rs");
 }